@@ -0,0 +1,32 @@
+//! Attribute macros for generating OpenRPC metadata without a build script.
+//!
+//! This crate provides the `#[openrpc_methods]` attribute, an alternative to running
+//! `openrpsee::generate_openrpc` from `build.rs` and including its output.
+
+use proc_macro::TokenStream;
+
+/// Generates the `METHODS` lookup table of the annotated RPC trait next to it.
+///
+/// Place the attribute above `#[rpc(...)]`, so that it sees the `#[method(...)]` and
+/// `#[openrpc(...)]` attributes of the trait's methods. The table is the one
+/// `openrpsee::generate_openrpc` writes for the trait, and the per-parameter constants
+/// are looked up the same way.
+///
+/// Options, all optional:
+///
+/// - `const_module = "crate::rpc"`: the module of the description constants, by
+///   default `crate::methods`.
+/// - `lazy_hash_map`: emits a `once_cell::sync::Lazy<HashMap<..>>` instead of a
+///   `phf::Map`.
+/// - `hash_map_fn`: emits a `methods()` function returning a fresh `HashMap` instead
+///   of the `METHODS` map.
+/// - `discover_fn`: also emits `document` and `discover` functions, returning the
+///   document of the methods, e.g. for an `rpc.discover` implementation.
+/// - `include_doc_hidden`: documents methods marked `#[doc(hidden)]`.
+/// - `strip_method_prefix = "internal_"`: strips the prefix from documented names.
+/// - `method_name_case = "camel-case"`: converts documented names to a convention,
+///   `"as-is"` (the default), `"camel-case"` or `"snake-case"`.
+#[proc_macro_attribute]
+pub fn openrpc_methods(attr: TokenStream, item: TokenStream) -> TokenStream {
+    openrpsee::openrpc_methods(attr.into(), item.into()).into()
+}
@@ -0,0 +1,424 @@
+//! Emission of the generated lookup table.
+
+use std::collections::BTreeSet;
+
+use crate::{ConstModule, DocCheck, GenerateOptions, MapKind, Overlay, parse::MethodInfo};
+
+/// Builds the contents of the generated lookup table.
+pub(crate) fn generate_contents(
+    methods: &[MethodInfo],
+    options: &GenerateOptions,
+    overlay: &Overlay,
+) -> String {
+    let mut contents = "/// Lookup table for JSON-RPC methods.
+#[allow(unused_qualifications)]
+"
+    .to_string();
+    contents.push_str(match options.map_kind {
+        MapKind::Phf => {
+            "pub static METHODS: ::phf::Map<&str, openrpsee::openrpc::RpcMethod> = ::phf::phf_map! {\n"
+        }
+        MapKind::LazyHashMap => {
+            "pub static METHODS: ::once_cell::sync::Lazy<
+    ::std::collections::HashMap<&str, openrpsee::openrpc::RpcMethod>,
+> = ::once_cell::sync::Lazy::new(|| ::std::collections::HashMap::from([\n"
+        }
+        MapKind::HashMapFn => {
+            "pub fn methods() -> ::std::collections::HashMap<&'static str, openrpsee::openrpc::RpcMethod> {
+    ::std::collections::HashMap::from([\n"
+        }
+    });
+
+    // Assertions that each parameter is documented, with `DocCheck::Deny`.
+    let mut assertions = String::new();
+
+    for method in methods {
+        match options.map_kind {
+            MapKind::Phf => contents.push_str(&format!("{:?} => ", method.command)),
+            MapKind::LazyHashMap | MapKind::HashMapFn => {
+                contents.push_str(&format!("({:?}, ", method.command))
+            }
+        }
+        contents.push_str("openrpsee::openrpc::RpcMethod {\n");
+
+        contents.push_str("    aliases: &[");
+        for alias in &method.aliases {
+            contents.push_str(&format!("{alias:?}, "));
+        }
+        contents.push_str("],\n");
+
+        // Group methods by their trait when several traits share a document, and by
+        // the tags they are given.
+        contents.push_str("    tags: &[");
+        if options.trait_names.len() > 1 {
+            contents.push_str(&format!("{:?}, ", method.trait_name));
+        }
+        for tag in &method.tags {
+            if options.trait_names.len() <= 1 || *tag != method.trait_name {
+                contents.push_str(&format!("{tag:?}, "));
+            }
+        }
+        contents.push_str("],\n");
+
+        // One literal per doc line keeps the generated code as readable as the doc
+        // comment itself.
+        contents.push_str("    description: concat!(\n");
+        for line in &method.doc_lines {
+            contents.push_str(&format!("        {:?},\n", format!("{line}\n")));
+        }
+        contents.push_str("    ),\n");
+
+        contents.push_str("    summary: ");
+        match &method.summary {
+            Some(summary) => contents.push_str(&format!("Some({summary:?})")),
+            None => contents.push_str("None"),
+        }
+        contents.push_str(",\n");
+
+        // The modules containing the per-parameter constants. Methods returning maps
+        // or other unnamed types don't name a module, and keep their `required`
+        // constants next to the others.
+        let in_module = |parent: &str| match method.module.as_str() {
+            "" => parent.to_string(),
+            module => format!("{parent}::{module}"),
+        };
+        let const_module = match &options.const_module {
+            ConstModule::Parent => in_module("super"),
+            ConstModule::Path(path) => path.clone(),
+        };
+        let required_module = match &options.const_module {
+            ConstModule::Parent => const_module.clone(),
+            ConstModule::Path(path) => in_module(path),
+        };
+
+        // The generator is named `_g` so that methods without parameters don't trigger
+        // an unused variable warning.
+        let mut params = "|_g| vec![\n".to_string();
+        for param in &method.params {
+            let (schema_ty, format) = options.param_schema_type(param);
+
+            if param.default_const.is_some() {
+                params.push_str("        _g.param_with_default::<");
+            } else {
+                params.push_str("        _g.param::<");
+            }
+            params.push_str(&schema_ty);
+            params.push_str(">(\"");
+            params.push_str(param.documented_name());
+            params.push_str("\", ");
+            params.push_str(&const_module);
+            params.push_str("::");
+            params.push_str(&options.const_naming.desc_const(&param.name));
+            params.push_str(", ");
+            if options.param_docs == DocCheck::Deny {
+                if let Some(cfg) = &method.cfg {
+                    assertions.push_str(&format!("#[cfg({cfg})]\n"));
+                }
+                assertions.push_str(&format!(
+                    "const _: () = assert!(\n    !{const_module}::{}.trim_ascii().is_empty(),\n    {:?},\n);\n",
+                    options.const_naming.desc_const(&param.name),
+                    format!(
+                        "method `{}`: parameter `{}` has no description",
+                        method.command, param.name
+                    ),
+                ));
+            }
+            match param.required {
+                Some(required) => params.push_str(&required.to_string()),
+                None => {
+                    // Require a helper const to be present.
+                    params.push_str(&required_module);
+                    params.push_str("::");
+                    params.push_str(&options.const_naming.required_const(&param.name));
+                }
+            }
+            if let Some(default_const) = &param.default_const {
+                // Unqualified constants live next to the description constants.
+                params.push_str(", &");
+                if !default_const.contains("::") {
+                    params.push_str(&const_module);
+                    params.push_str("::");
+                }
+                params.push_str(default_const);
+            }
+            params.push(')');
+            push_format(&mut params, format);
+            if param.nullable {
+                params.push_str(".nullable()");
+            }
+            if param.deprecated {
+                params.push_str(&format!(".deprecated({:?})", param.deprecation_note));
+            }
+            params.push_str(",\n");
+        }
+        if method.params.is_empty() {
+            params = "|_g| vec![]".into();
+        } else {
+            params.push_str("    ]");
+        }
+
+        let mut result = String::new();
+        match (&method.item, &method.result_schema_ty) {
+            (Some(item), _) => {
+                let (schema_ty, format) = options.schema_type(item);
+
+                result.push_str("|g| g.item::<");
+                result.push_str(&schema_ty);
+                result.push_str(">(\"");
+                result.push_str(&method.command);
+                result.push_str("_item\")");
+                push_format(&mut result, format);
+            }
+            (None, Some(result_schema_ty)) => {
+                let (schema_ty, format) = options.schema_type(result_schema_ty);
+
+                result.push_str("|g| g.result_of::<");
+                result.push_str(&schema_ty);
+                result.push_str(">(\"");
+                result.push_str(&method.command);
+                result.push_str(&options.result_name_suffix);
+                result.push_str("\")");
+                push_format(&mut result, format);
+            }
+            (None, None) => {
+                result.push_str("|g| g.result::<openrpsee::openrpc");
+                result.push_str("::ResultType>(\"");
+                result.push_str(&method.command);
+                result.push_str(&options.result_name_suffix);
+                result.push_str("\")");
+            }
+        }
+        if method.result_nullable {
+            result.push_str(".nullable()");
+        }
+
+        // Note the local type aliases the schemas see through, unless the schema is
+        // given explicitly.
+        for param in method.params.iter().filter(|param| param.schema.is_none()) {
+            if let Some(aliased_ty) = &param.aliased_ty {
+                contents.push_str(&format!(
+                    "    // Parameter `{}`: `{aliased_ty}` is documented as the aliased `{}`.\n",
+                    param.name, param.schema_ty
+                ));
+            }
+        }
+        for param in method.params.iter().filter(|param| param.schema.is_none()) {
+            if let Some(note) = options.override_note(&param.schema_ty) {
+                contents.push_str(&format!(
+                    "    // Parameter `{}`: `{}` is {note}.\n",
+                    param.name, param.schema_ty
+                ));
+            }
+        }
+        if let Some(schema_ty) = method.item.as_ref().or(method.result_schema_ty.as_ref())
+            && let Some(note) = options.override_note(schema_ty)
+        {
+            contents.push_str(&format!("    // Result: `{schema_ty}` is {note}.\n"));
+        }
+        if let Some(aliased_ty) = &method.aliased_result_ty
+            && let Some(schema_ty) = method.item.as_ref().or(method.result_schema_ty.as_ref())
+        {
+            contents.push_str(&format!(
+                "    // Result: `{aliased_ty}` is documented as the aliased `{schema_ty}`.\n"
+            ));
+        }
+
+        match &method.cfg {
+            None => {
+                contents.push_str("    available: true,\n");
+                contents.push_str(&format!("    params: {params},\n"));
+                contents.push_str(&format!("    result: {result},\n"));
+            }
+            Some(cfg) => {
+                // `phf_map!` doesn't support `#[cfg]` on individual entries, so the entry
+                // is always present, but only refers to the method's types when they are
+                // compiled in.
+                contents.push_str(&format!("    available: cfg!({cfg}),\n"));
+                contents.push_str(&format!(
+                    "    params: {{
+        #[cfg({cfg})]
+        let params: fn(&mut openrpsee::openrpc::Generator) -> Vec<openrpsee::openrpc::ContentDescriptor> = {params};
+        #[cfg(not({cfg}))]
+        let params: fn(&mut openrpsee::openrpc::Generator) -> Vec<openrpsee::openrpc::ContentDescriptor> = |_g| vec![];
+        params
+    }},\n"
+                ));
+                contents.push_str(&format!(
+                    "    result: {{
+        #[cfg({cfg})]
+        let result: fn(&mut openrpsee::openrpc::Generator) -> openrpsee::openrpc::ContentDescriptor = {result};
+        #[cfg(not({cfg}))]
+        let result: fn(&mut openrpsee::openrpc::Generator) -> openrpsee::openrpc::ContentDescriptor = |g| g.item::<()>(\"unavailable\");
+        result
+    }},\n"
+                ));
+            }
+        }
+
+        contents.push_str("    deprecated: ");
+        contents.push_str(&method.deprecated.to_string());
+        contents.push_str(",\n");
+
+        contents.push_str("    replaced_by: ");
+        match &method.replaced_by {
+            Some(replaced_by) => {
+                contents.push_str(&format!("Some({:?})", options.documented_name(replaced_by)))
+            }
+            None => contents.push_str("None"),
+        }
+        contents.push_str(",\n");
+
+        contents.push_str("    deprecation_note: ");
+        match &method.deprecation_note {
+            Some(note) => contents.push_str(&format!("Some({note:?})")),
+            None => contents.push_str("None"),
+        }
+        contents.push_str(",\n");
+
+        contents.push_str("    subscription: ");
+        contents.push_str(&method.item.is_some().to_string());
+        contents.push_str(",\n");
+
+        contents.push_str("    unsubscribe: ");
+        match &method.unsubscribe {
+            Some(unsubscribe) => contents.push_str(&format!("Some({unsubscribe:?})")),
+            None => contents.push_str("None"),
+        }
+        contents.push_str(",\n");
+
+        contents.push_str("    cost: ");
+        match &method.cost {
+            Some(cost) => contents.push_str(&format!(
+                "Some(openrpsee::openrpc::Cost {{ weight: {:?}, timeout_ms: {:?} }})",
+                cost.weight, cost.timeout_ms
+            )),
+            None => contents.push_str("None"),
+        }
+        contents.push_str(",\n");
+
+        contents.push_str("    result_examples: &[");
+        for (name, value) in &method.result_examples {
+            contents.push_str(&format!("({name:?}, {value:?}), "));
+        }
+        contents.push_str("],\n");
+
+        contents.push_str("    examples: &[");
+        for example in &method.examples {
+            contents.push_str(&format!(
+                "openrpsee::openrpc::MethodExample {{ name: {:?}, params: &[",
+                example.name
+            ));
+            for (name, value) in &example.params {
+                contents.push_str(&format!("({name:?}, {value:?}), "));
+            }
+            contents.push_str(&format!("], result: {:?} }}, ", example.result));
+        }
+        contents.push_str("],\n");
+
+        contents.push_str("    errors: &[");
+        for error in &method.errors {
+            contents.push_str(&format!(
+                "openrpsee::openrpc::MethodError {{ name: {:?}, code: {}, message: {:?}, data: {:?} }}, ",
+                error.name, error.code, error.message, error.data
+            ));
+        }
+        contents.push_str("],\n");
+
+        contents.push_str(&format!(
+            "    param_structure: openrpsee::openrpc::ParamStructure::{:?},\n",
+            method.param_structure
+        ));
+
+        contents.push_str("    public_name: ");
+        match options.documented_name(&method.command) {
+            name if name != method.command => contents.push_str(&format!("Some({name:?})")),
+            _ => contents.push_str("None"),
+        }
+        contents.push_str(",\n");
+
+        contents.push_str("    overlay: ");
+        match overlay.methods.get(&method.command) {
+            Some(method_overlay) => contents.push_str(&format!("Some({method_overlay:?})")),
+            None => contents.push_str("None"),
+        }
+        contents.push_str(",\n");
+
+        match options.map_kind {
+            MapKind::Phf => contents.push_str("},\n"),
+            MapKind::LazyHashMap | MapKind::HashMapFn => contents.push_str("}),\n"),
+        }
+    }
+
+    match options.map_kind {
+        MapKind::Phf => contents.push_str("};"),
+        MapKind::LazyHashMap => contents.push_str("]));"),
+        MapKind::HashMapFn => contents.push_str("])\n}"),
+    }
+
+    contents.push_str(&format!(
+        "
+
+/// Fields merged over the top level of the OpenRPC document, as a JSON object.
+pub static DOCUMENT_OVERLAY: &str = {:?};",
+        overlay.document
+    ));
+
+    if options.discover_fn {
+        let methods = match options.map_kind {
+            MapKind::Phf => "METHODS.entries()",
+            MapKind::LazyHashMap => "METHODS.iter()",
+            MapKind::HashMapFn => "&methods()",
+        };
+        contents.push_str(&format!(
+            "
+
+/// Returns the OpenRPC document of the generated methods.
+///
+/// The document is built on the first call, with the API information returned by
+/// `info`, and cached for the rest of the process.
+pub fn document(
+    info: impl FnOnce() -> openrpsee::openrpc::Info,
+) -> &'static openrpsee::openrpc::OpenRpc {{
+    static DOCUMENT: openrpsee::openrpc::CachedDocument =
+        openrpsee::openrpc::CachedDocument::new();
+    DOCUMENT.get_or_build(info, {methods})
+}}
+
+/// Returns the OpenRPC document of the generated methods, for `rpc.discover`.
+///
+/// See [`document`].
+pub fn discover(
+    info: impl FnOnce() -> openrpsee::openrpc::Info,
+) -> openrpsee::openrpc::Response {{
+    Ok(document(info).clone())
+}}"
+        ));
+    }
+
+    if !assertions.is_empty() {
+        contents.push_str("\n\n");
+        contents.push_str(&assertions);
+    }
+
+    contents
+}
+
+/// Overrides the `format` of the preceding descriptor's schema, if requested.
+fn push_format(contents: &mut String, format: Option<&str>) {
+    if let Some(format) = format {
+        contents.push_str(&format!(".with_format({format:?})"));
+    }
+}
+
+/// Builds empty stubs of the given parameter description constants.
+pub(crate) fn param_desc_stubs(names: &BTreeSet<String>) -> String {
+    let mut contents = String::new();
+    contents.push_str(
+        "// Stubs of the parameter description constants missing from the source files.\n",
+    );
+    contents.push_str("// Define a constant there to document its parameter and drop its stub.\n");
+    for name in names {
+        contents.push_str(&format!("pub const {name}: &str = \"\";\n"));
+    }
+    contents
+}
@@ -0,0 +1,85 @@
+//! Expansion of the `#[openrpc_methods]` attribute of `openrpsee-macros`.
+
+use std::{collections::HashMap, path::Path};
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+
+use crate::{ConstModule, GenerateOptions, MapKind, NameCase, Overlay, emit, parse};
+
+/// Expands `#[openrpc_methods(...)]` on the trait `item` into the trait itself,
+/// followed by the lookup table of its methods.
+///
+/// This is the implementation of `openrpsee_macros::openrpc_methods`, which can't live
+/// in a proc-macro crate, as it shares its parsing with [`GenerateOptions`].
+pub fn openrpc_methods(attr: TokenStream, item: TokenStream) -> TokenStream {
+    match expand(attr, item.clone()) {
+        Ok(tokens) => tokens,
+        // Keep the trait, so that our error doesn't hide those of its uses.
+        Err(e) => {
+            let mut tokens = item;
+            tokens.extend(e.to_compile_error());
+            tokens
+        }
+    }
+}
+
+/// Expands `#[openrpc_methods(...)]`, see [`openrpc_methods`].
+fn expand(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let tr = syn::parse2::<syn::ItemTrait>(item)?;
+    let name = tr.ident.to_string();
+
+    // Nothing is read or written, so the paths are only used in messages.
+    let mut options = GenerateOptions::new(&name, &[&name], Path::new("")).rerun_if_changed(false);
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("const_module") {
+            let path = meta.value()?.parse::<syn::LitStr>()?.value();
+            options.const_module = ConstModule::Path(path);
+        } else if meta.path.is_ident("lazy_hash_map") {
+            options.map_kind = MapKind::LazyHashMap;
+        } else if meta.path.is_ident("hash_map_fn") {
+            options.map_kind = MapKind::HashMapFn;
+        } else if meta.path.is_ident("discover_fn") {
+            options.discover_fn = true;
+        } else if meta.path.is_ident("include_doc_hidden") {
+            options.include_doc_hidden = true;
+        } else if meta.path.is_ident("strip_method_prefix") {
+            options.strip_method_prefix = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+        } else if meta.path.is_ident("method_name_case") {
+            let lit = meta.value()?.parse::<syn::LitStr>()?;
+            options.method_name_case = match lit.value().as_str() {
+                "as-is" => NameCase::AsIs,
+                "camel-case" => NameCase::CamelCase,
+                "snake-case" => NameCase::SnakeCase,
+                _ => {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        "expected `as-is`, `camel-case` or `snake-case`",
+                    ));
+                }
+            };
+        } else {
+            return Err(meta.error("unsupported `openrpc_methods` option"));
+        }
+        Ok(())
+    });
+    syn::parse::Parser::parse2(parser, attr)?;
+
+    // Only the trait itself is visible to the attribute, so type aliases defined next
+    // to it aren't resolved.
+    let mut methods = parse::trait_methods(&tr, &HashMap::new(), &HashMap::new())?;
+    if !options.include_doc_hidden {
+        methods.retain(|method| !method.doc_hidden);
+    }
+    parse::check_methods(&methods)
+        .and_then(|()| options.check_documented_names(&methods))
+        .map_err(|e| syn::Error::new(Span::call_site(), e))?;
+
+    let table = emit::generate_contents(&methods, &options, &Overlay::default())
+        .parse::<TokenStream>()
+        .map_err(|e| syn::Error::new(Span::call_site(), e))?;
+    Ok(quote! {
+        #tr
+        #table
+    })
+}
@@ -3,213 +3,1186 @@
 //! This crate provides utilities to generate OpenRPC documents
 //! in projects that uses the `jsonrpsee` crate for JSON-RPC method definitions.
 
-use std::{error::Error, fs, path::Path};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
 
+use heck::{ToLowerCamelCase, ToShoutySnakeCase, ToSnakeCase};
 use quote::ToTokens;
 
+mod emit;
+mod expand;
 pub mod openrpc;
+mod parse;
+#[cfg(test)]
+mod tests;
+
+#[doc(hidden)]
+pub use expand::openrpc_methods;
+pub use parse::{ErrorInfo, MethodInfo, ParamInfo};
 
 /// Generates a lookup table for the JSON-RPC methods defined in the given source file.
 ///
 /// This function is meant to be used in the build script (`build.rs`) of a project.
+/// It is a shorthand for [`GenerateOptions`] with default settings.
 pub fn generate_openrpc(
     json_rpc_methods_rs: &str,
     trait_names: &[&str],
     use_parent_module: bool,
     out_dir: &Path,
 ) -> Result<(), Box<dyn Error>> {
-    // Parse the source file containing the requested traits.
-    let methods_rs = fs::read_to_string(json_rpc_methods_rs)?;
-    let methods_ast = syn::parse_file(&methods_rs)?;
+    GenerateOptions::new(json_rpc_methods_rs, trait_names, out_dir)
+        .use_parent_module(use_parent_module)
+        .generate()
+}
 
-    // Collect references to all requested traits.
-    let traits: Vec<&syn::ItemTrait> = trait_names
-        .iter()
-        .map(|name| {
-            methods_ast
-                .items
+/// Like [`generate_openrpc`], but skips regeneration when the inputs are unchanged.
+///
+/// See [`GenerateOptions::cache`] for details.
+pub fn generate_openrpc_cached(
+    json_rpc_methods_rs: &str,
+    trait_names: &[&str],
+    use_parent_module: bool,
+    out_dir: &Path,
+) -> Result<(), Box<dyn Error>> {
+    GenerateOptions::new(json_rpc_methods_rs, trait_names, out_dir)
+        .use_parent_module(use_parent_module)
+        .cache(true)
+        .generate()
+}
+
+/// Generates the lookup table for the JSON-RPC methods defined in the given source code,
+/// and returns it.
+///
+/// This is [`generate_openrpc`] without any files involved, e.g. for tests. It is a
+/// shorthand for [`GenerateOptions::generate_from_str`] with default settings.
+pub fn generate_rpc_openrpc_from_str(
+    source: &str,
+    trait_names: &[&str],
+) -> Result<String, Box<dyn Error>> {
+    GenerateOptions::new(SOURCE_STR_NAME, trait_names, Path::new("")).generate_from_str(source)
+}
+
+/// Options controlling how the lookup table is generated.
+///
+/// Start from [`GenerateOptions::new`], adjust the options with the builder methods,
+/// and call [`GenerateOptions::generate`].
+#[derive(Clone, Debug, Hash)]
+pub struct GenerateOptions {
+    source: PathBuf,
+    additional_sources: Vec<PathBuf>,
+    trait_names: Vec<String>,
+    output: PathBuf,
+    const_module: ConstModule,
+    const_naming: ConstNaming,
+    type_overrides: BTreeMap<String, SchemaOverride>,
+    cache: bool,
+    warn_deprecated: bool,
+    follow_reexports: bool,
+    include_doc_hidden: bool,
+    result_aliases: BTreeMap<String, String>,
+    map_kind: MapKind,
+    strip_method_prefix: Option<String>,
+    method_name_case: NameCase,
+    param_docs: DocCheck,
+    missing_required: DocCheck,
+    result_name_suffix: String,
+    rerun_if_changed: bool,
+    param_desc_stubs: bool,
+    discover_fn: bool,
+}
+
+impl GenerateOptions {
+    /// Creates options that read the given traits from `source` and write the lookup
+    /// table to `rpc_openrpc.rs` within `out_dir`.
+    pub fn new(source: impl AsRef<Path>, trait_names: &[&str], out_dir: &Path) -> Self {
+        Self {
+            source: source.as_ref().to_path_buf(),
+            additional_sources: vec![],
+            trait_names: trait_names.iter().map(|name| name.to_string()).collect(),
+            output: out_dir.join(OUTPUT_FILE),
+            const_module: ConstModule::default(),
+            const_naming: ConstNaming::default(),
+            type_overrides: DEFAULT_TYPE_OVERRIDES
                 .iter()
-                .find_map(|item| match item {
-                    syn::Item::Trait(item_trait) if item_trait.ident == *name => Some(item_trait),
-                    _ => None,
-                })
-                .unwrap_or_else(|| {
-                    panic!("trait `{name}` must be present in {json_rpc_methods_rs}")
+                .map(|(rust_ty, schema_ty)| {
+                    (normalize_type(rust_ty), SchemaOverride::new(schema_ty))
                 })
+                .collect(),
+            cache: false,
+            warn_deprecated: false,
+            follow_reexports: false,
+            include_doc_hidden: false,
+            result_aliases: BTreeMap::new(),
+            map_kind: MapKind::default(),
+            strip_method_prefix: None,
+            method_name_case: NameCase::default(),
+            param_docs: DocCheck::default(),
+            missing_required: DocCheck::Warn,
+            result_name_suffix: "_result".into(),
+            // Build scripts, unlike the crates they build, are run with `TARGET` set.
+            rerun_if_changed: std::env::var_os("TARGET").is_some()
+                && std::env::var_os("OUT_DIR").is_some(),
+            param_desc_stubs: false,
+            discover_fn: false,
+        }
+    }
+
+    /// Adds another source file in which to look up the traits, for projects that split
+    /// their RPC traits across several files.
+    ///
+    /// Each trait must be defined in exactly one of the source files; one that is only
+    /// re-exported is looked up through its re-export. The overlay file is still
+    /// located next to the main source file.
+    pub fn additional_source(mut self, source: impl AsRef<Path>) -> Self {
+        self.additional_sources.push(source.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the path the lookup table is written to.
+    ///
+    /// Defaults to `rpc_openrpc.rs` within the output directory.
+    pub fn output(mut self, output: impl AsRef<Path>) -> Self {
+        self.output = output.as_ref().to_path_buf();
+        self
+    }
+
+    /// Sets whether the per-parameter constants are looked up in the parent module of
+    /// the generated file, within a submodule named after the first path segment of
+    /// each method's return type.
+    ///
+    /// This is a shorthand for [`GenerateOptions::const_module`] with either
+    /// [`ConstModule::Parent`] or the default [`ConstModule::Path`].
+    pub fn use_parent_module(self, use_parent_module: bool) -> Self {
+        self.const_module(if use_parent_module {
+            ConstModule::Parent
+        } else {
+            ConstModule::default()
         })
-        .collect();
+    }
 
-    let mut contents = "/// Lookup table for JSON-RPC methods.
-#[allow(unused_qualifications)]
-pub static METHODS: ::phf::Map<&str, openrpsee::openrpc::RpcMethod> = ::phf::phf_map! {
-"
-    .to_string();
+    /// Sets the kind of map the generated `METHODS` table is.
+    ///
+    /// The static kinds are looked up the same way, with `METHODS.get(name)`. With
+    /// [`MapKind::HashMapFn`], the table is built by calling `methods()` instead.
+    pub fn map_kind(mut self, map_kind: MapKind) -> Self {
+        self.map_kind = map_kind;
+        self
+    }
 
-    for item in traits.iter().flat_map(|tr| tr.items.iter()) {
-        if let syn::TraitItem::Fn(method) = item {
-            // Find methods via their `#[method(name = "command")]` attribute.
-            let mut command = None;
-            method
-                .attrs
-                .iter()
-                .find(|attr| attr.path().is_ident("method"))
-                .and_then(|attr| {
-                    attr.parse_nested_meta(|meta| {
-                        command = Some(meta.value()?.parse::<syn::LitStr>()?.value());
-                        Ok(())
-                    })
-                    .ok()
+    /// Sets the module the per-parameter constants are looked up in.
+    pub fn const_module(mut self, const_module: ConstModule) -> Self {
+        self.const_module = const_module;
+        self
+    }
+
+    /// Sets how the names of the per-parameter constants are derived.
+    pub fn const_naming(mut self, const_naming: ConstNaming) -> Self {
+        self.const_naming = const_naming;
+        self
+    }
+
+    /// Documents parameters, results, and subscription items of type `rust_ty` with the
+    /// given schema instead of the type's own.
+    ///
+    /// `rust_ty` is matched against the type as written in the trait, ignoring
+    /// whitespace (e.g. `"BlockHash"` or `"primitives::BlockHash"`). This is useful for
+    /// types that don't implement `JsonSchema`, or whose derived schema doesn't match
+    /// their serialization, such as byte newtypes serialized as hex strings (see
+    /// [`SchemaOverride::hex_bytes`]).
+    ///
+    /// By default, `age::secrecy::SecretString` is documented as a `String`; call
+    /// [`GenerateOptions::clear_type_overrides`] first to opt out of it.
+    pub fn type_override(mut self, rust_ty: &str, schema: SchemaOverride) -> Self {
+        self.type_overrides.insert(normalize_type(rust_ty), schema);
+        self
+    }
+
+    /// Removes all type overrides registered so far, including the default ones (see
+    /// [`GenerateOptions::type_override`]).
+    pub fn clear_type_overrides(mut self) -> Self {
+        self.type_overrides.clear();
+        self
+    }
+
+    /// Documents `i128` and `u128` as decimal strings, with the overrides of
+    /// [`SchemaOverride::i128_string`] and [`SchemaOverride::u128_string`].
+    ///
+    /// JSON numbers can't carry every 128-bit integer to clients that parse them as
+    /// 64-bit floats, so this suits APIs serializing these integers as strings.
+    pub fn int128_as_string(self) -> Self {
+        self.type_override("i128", SchemaOverride::i128_string())
+            .type_override("u128", SchemaOverride::u128_string())
+    }
+
+    /// Documents the foreign type `foreign_ty` with the schema of `shadow_ty`, a local
+    /// type implementing `JsonSchema` in its place (e.g. through schemars'
+    /// `#[serde(remote = "...")]` support).
+    ///
+    /// This is a shorthand for [`GenerateOptions::type_override`] with
+    /// [`SchemaOverride::new`]. Like all overrides without a format, it also applies
+    /// where `foreign_ty` is nested in another type, e.g. `Vec<ForeignType>` or
+    /// `Option<ForeignType>`.
+    pub fn remote_type(self, foreign_ty: &str, shadow_ty: &str) -> Self {
+        self.type_override(foreign_ty, SchemaOverride::new(shadow_ty))
+    }
+
+    /// Documents methods returning the type alias `alias` as returning `target`.
+    ///
+    /// Methods returning `RpcResult<T>`, `Result<T, E>`, or `ResponsePayload<'a, T>`
+    /// are documented with `T` as their result. Aliases of those (e.g.
+    /// `type MethodResult = RpcResult<Balance>;`) defined in the same file as the trait
+    /// are resolved automatically; this covers aliases defined elsewhere. `alias` is
+    /// matched against the return type as written, ignoring whitespace, and `target` is
+    /// a Rust type such as `"RpcResult<Balance>"`.
+    pub fn result_alias(mut self, alias: &str, target: &str) -> Self {
+        self.result_aliases
+            .insert(normalize_type(alias), target.to_string());
+        self
+    }
+
+    /// Sets whether to skip regeneration when the inputs are unchanged.
+    ///
+    /// A fingerprint of the source file path and contents, the generation options,
+    /// and the version of this crate is written next to the generated file, along
+    /// with the `cargo:warning`s of the run. If a later call computes the same
+    /// fingerprint and the generated file still exists, nothing is parsed or written,
+    /// and the recorded warnings are emitted again.
+    pub fn cache(mut self, cache: bool) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Sets whether to emit a `cargo:warning` for each deprecated method.
+    ///
+    /// This surfaces the remaining deprecated methods in the build output without
+    /// failing the build.
+    pub fn warn_deprecated(mut self, warn_deprecated: bool) -> Self {
+        self.warn_deprecated = warn_deprecated;
+        self
+    }
+
+    /// Sets whether traits that are re-exported into the source file (e.g. through
+    /// `pub use api::Api;`) are looked up in the file of the module they come from.
+    ///
+    /// Module files are located the way `rustc` does for `mod` declarations without a
+    /// `#[path]` attribute, starting from the source file. Paths through `crate::` or
+    /// other crates are not supported. The contents of those other files are not part
+    /// of the [`GenerateOptions::cache`] fingerprint, so caching is bypassed while this
+    /// is enabled.
+    pub fn follow_reexports(mut self, follow_reexports: bool) -> Self {
+        self.follow_reexports = follow_reexports;
+        self
+    }
+
+    /// Sets whether methods marked `#[doc(hidden)]` are documented.
+    ///
+    /// By default they are left out of the lookup table, as such methods are usually
+    /// internal; they remain callable either way.
+    pub fn include_doc_hidden(mut self, include_doc_hidden: bool) -> Self {
+        self.include_doc_hidden = include_doc_hidden;
+        self
+    }
+
+    /// Strips `prefix` from the names of the methods in generated documents, e.g. to
+    /// document `internal_getinfo` as `getinfo`.
+    ///
+    /// The lookup table is still keyed by the real names, which are preserved in an
+    /// `x-internal-name` extension of each renamed method. Names in
+    /// `deprecated_replaced_by` are stripped too, so they keep pointing at documented
+    /// methods; aliases are left as they are.
+    pub fn strip_method_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.strip_method_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Converts the names of the methods in generated documents to a single
+    /// convention, e.g. to document `get_info` as `getInfo`.
+    ///
+    /// As with [`GenerateOptions::strip_method_prefix`], which is applied first, the
+    /// lookup table stays keyed by the real names and renamed methods keep theirs in
+    /// an `x-internal-name` extension. Names in `deprecated_replaced_by` are converted
+    /// too; aliases are left as they are.
+    pub fn method_name_case(mut self, case: NameCase) -> Self {
+        self.method_name_case = case;
+        self
+    }
+
+    /// Sets how parameters without a description are reported.
+    ///
+    /// A parameter is undocumented when its description constant is empty or only
+    /// whitespace. With [`DocCheck::Warn`], a `cargo:warning` is emitted for each such
+    /// constant defined as a string literal in the source file: in the module named
+    /// after the return type with [`ConstModule::Parent`], or at the top level with
+    /// [`ConstModule::Path`]. Constants defined elsewhere can't be checked from the
+    /// build script. With [`DocCheck::Deny`], the generated file asserts that every
+    /// description constant is non-empty, so any undocumented parameter fails the
+    /// build wherever its constant is defined.
+    pub fn param_docs(mut self, param_docs: DocCheck) -> Self {
+        self.param_docs = param_docs;
+        self
+    }
+
+    /// Sets how parameters whose requiredness isn't known are reported.
+    ///
+    /// Whether a parameter such as a `Vec<T>` may be left out can't be told from its
+    /// type, so it's stated with `#[openrpc(param(name = "...", required = ...))]` or
+    /// a `required` constant. When the source files show that neither is there,
+    /// [`DocCheck::Warn`], the default, documents the parameter as required with a
+    /// `cargo:warning`, [`DocCheck::Allow`] does so silently, and [`DocCheck::Deny`]
+    /// fails the build.
+    pub fn missing_required(mut self, missing_required: DocCheck) -> Self {
+        self.missing_required = missing_required;
+        self
+    }
+
+    /// Sets the suffix appended to the method name to name its result descriptor.
+    ///
+    /// Defaults to `_result`, naming e.g. the result of `getinfo` `getinfo_result`. An
+    /// empty suffix names the descriptor exactly after the method.
+    pub fn result_name_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.result_name_suffix = suffix.into();
+        self
+    }
+
+    /// Sets whether to emit `cargo:rerun-if-changed` for the files the lookup table is
+    /// generated from, so that Cargo reruns the build script when they change.
+    ///
+    /// This covers the source files, the overlay file if there is one, and the files of
+    /// traits followed through re-exports. Defaults to whether the generator runs in a
+    /// build script. Once a build script emits any `rerun-if-changed`, Cargo no longer
+    /// reruns it when other files of the package change, so list the build script's
+    /// other inputs as well (e.g. `cargo:rerun-if-changed=build.rs`), or disable this.
+    pub fn rerun_if_changed(mut self, rerun_if_changed: bool) -> Self {
+        self.rerun_if_changed = rerun_if_changed;
+        self
+    }
+
+    /// Sets whether to also write empty stubs of the missing parameter description
+    /// constants to `rpc_param_descs.rs`, next to the lookup table.
+    ///
+    /// Only the constants the source files don't define are stubbed, so documenting a
+    /// parameter is a matter of defining its constant in the source file, which drops
+    /// its stub on the next build. The stubs are meant to be included from the module
+    /// holding the constants, which requires [`ConstModule::Path`].
+    pub fn param_desc_stubs(mut self, param_desc_stubs: bool) -> Self {
+        self.param_desc_stubs = param_desc_stubs;
+        self
+    }
+
+    /// Sets whether to also generate `document` and `discover` functions, returning
+    /// the document of the generated methods, e.g. for an `rpc.discover`
+    /// implementation.
+    ///
+    /// `document(|| info)` builds the document with [`openrpc::OpenRpc::build`] on
+    /// its first call, using the API information returned by the closure, and returns
+    /// the same `&'static` document from then on, through an
+    /// [`openrpc::CachedDocument`]. `discover(|| info)` returns a copy of it as an
+    /// [`openrpc::Response`]. Fields of an overlay file's `DOCUMENT_OVERLAY` aren't
+    /// applied to it.
+    pub fn discover_fn(mut self, discover_fn: bool) -> Self {
+        self.discover_fn = discover_fn;
+        self
+    }
+
+    /// Generates the lookup table.
+    pub fn generate(&self) -> Result<(), Box<dyn Error>> {
+        let methods_rs = fs::read_to_string(&self.source)?;
+        let sources = self.sources(&methods_rs)?;
+        if self.rerun_if_changed {
+            for (source, _) in &sources {
+                emit_rerun_if_changed(source);
+            }
+            let overlay_path = self.overlay_path();
+            if overlay_path.is_file() {
+                emit_rerun_if_changed(&overlay_path);
+            }
+        }
+
+        let fingerprint_path = self.output.with_extension(FINGERPRINT_EXTENSION);
+        let fingerprint =
+            (self.cache && !self.follow_reexports).then(|| self.fingerprint(&sources));
+
+        let stubs_path = self.output.with_file_name(PARAM_DESC_STUBS_FILE);
+        if let Some(fingerprint) = &fingerprint
+            && self.output.exists()
+            && (!self.param_desc_stubs || stubs_path.exists())
+            && let Ok(cached) = fs::read_to_string(&fingerprint_path)
+            && let Some((cached_fingerprint, warnings)) = cached.split_once('\n')
+            && cached_fingerprint == fingerprint
+        {
+            emit_warnings(warnings.lines());
+            return Ok(());
+        }
+
+        let mut warnings = vec![];
+        let result = self.generate_from_sources(&sources, &mut warnings);
+        emit_warnings(warnings.iter().map(String::as_str));
+        let (methods, contents) = result?;
+        fs::write(&self.output, contents)?;
+        if self.param_desc_stubs {
+            fs::write(
+                &stubs_path,
+                self.param_desc_stubs_contents(&methods, &sources)?,
+            )?;
+        }
+        if let Some(mut fingerprint) = fingerprint {
+            fingerprint.push('\n');
+            for warning in &warnings {
+                fingerprint.push_str(warning);
+                fingerprint.push('\n');
+            }
+            fs::write(&fingerprint_path, fingerprint)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the fingerprint of the inputs of [`GenerateOptions::generate`], as
+    /// written next to the generated file when caching.
+    fn fingerprint(&self, sources: &[Source<'_>]) -> String {
+        let mut hasher = StableHasher::default();
+        hasher.write(env!("CARGO_PKG_VERSION").as_bytes());
+        for (path, source_rs) in sources {
+            hasher.write(path.to_string_lossy().as_bytes());
+            hasher.write(source_rs.as_bytes());
+        }
+        match fs::read(self.overlay_path()) {
+            Ok(overlay) => hasher.write(&overlay),
+            Err(_) => hasher.write(b"no overlay"),
+        }
+        hasher.write(format!("{self:?}").as_bytes());
+        format!("{:016x}", hasher.finish())
+    }
+
+    /// Generates the lookup table from the given source instead of reading the source
+    /// file, and returns it instead of writing it.
+    ///
+    /// The source file path is still used in error messages, as the starting point for
+    /// [`GenerateOptions::follow_reexports`], and to locate the overlay file. Additional
+    /// source files are read as usual.
+    pub fn generate_from_str(&self, methods_rs: &str) -> Result<String, Box<dyn Error>> {
+        let mut warnings = vec![];
+        let result = self.generate_from_sources(&self.sources(methods_rs)?, &mut warnings);
+        emit_warnings(warnings.iter().map(String::as_str));
+        Ok(result?.1)
+    }
+
+    /// Generates the lookup table from the given source files, returning it along with
+    /// the methods it contains, and adding the build warnings to `warnings`.
+    fn generate_from_sources(
+        &self,
+        sources: &[Source<'_>],
+        warnings: &mut Vec<String>,
+    ) -> Result<(Vec<MethodInfo>, String), Box<dyn Error>> {
+        let methods = self.parse_methods(sources, warnings)?;
+        if self.warn_deprecated {
+            for method in methods.iter().filter(|method| method.deprecated) {
+                warnings.push(match &method.deprecation_note {
+                    Some(note) => format!("method '{}' is deprecated: {note}", method.command),
+                    None => format!("method '{}' is deprecated", method.command),
                 });
+            }
+        }
+        self.check_documented_names(&methods)
+            .map_err(|e| format!("{}: {e}", self.source_name()))?;
+        if self.param_docs == DocCheck::Warn {
+            self.warn_param_docs(&methods, sources, warnings)?;
+        }
+        let overlay = self.read_overlay(&methods)?;
+        let contents = emit::generate_contents(&methods, self, &overlay);
+        Ok((methods, contents))
+    }
 
-            if let Some(command) = command {
-                let module = match &method.sig.output {
-                    syn::ReturnType::Type(_, ret) => match ret.as_ref() {
-                        syn::Type::Path(type_path) => type_path.path.segments.first(),
-                        _ => None,
-                    },
-                    _ => None,
+    /// Checks that no two methods are documented under the same name.
+    fn check_documented_names(&self, methods: &[MethodInfo]) -> Result<(), String> {
+        let mut documented = BTreeMap::new();
+        for method in methods {
+            let name = self.documented_name(&method.command);
+            if let Some(other) = documented.insert(name.clone(), &method.command) {
+                return Err(format!(
+                    "methods `{other}` and `{}` are both documented as `{name}`",
+                    method.command,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds the stubs of the parameter description constants of `methods` that the
+    /// source files don't define.
+    fn param_desc_stubs_contents(
+        &self,
+        methods: &[MethodInfo],
+        sources: &[Source<'_>],
+    ) -> Result<String, Box<dyn Error>> {
+        if self.const_module == ConstModule::Parent {
+            return Err(
+                "`param_desc_stubs` requires `ConstModule::Path`, as the constants \
+                        of `ConstModule::Parent` are spread across modules"
+                    .into(),
+            );
+        }
+        let asts = sources
+            .iter()
+            .map(|(_, source_rs)| syn::parse_file(source_rs))
+            .collect::<Result<Vec<_>, _>>()?;
+        let missing: BTreeSet<_> = methods
+            .iter()
+            .flat_map(|method| &method.params)
+            .map(|param| self.const_naming.desc_const(&param.name))
+            .filter(|name| !asts.iter().any(|ast| parse::defines_const(ast, name)))
+            .collect();
+        Ok(emit::param_desc_stubs(&missing))
+    }
+
+    /// Parses the source files and returns the methods that would be generated.
+    pub fn methods(&self) -> Result<Vec<MethodInfo>, Box<dyn Error>> {
+        let methods_rs = fs::read_to_string(&self.source)?;
+        let mut warnings = vec![];
+        let methods = self.parse_methods(&self.sources(&methods_rs)?, &mut warnings);
+        emit_warnings(warnings.iter().map(String::as_str));
+        methods
+    }
+
+    /// Pairs the main source, given as `methods_rs`, with the contents of the
+    /// additional source files.
+    fn sources<'a>(&'a self, methods_rs: &'a str) -> Result<Vec<Source<'a>>, Box<dyn Error>> {
+        let mut sources = vec![(self.source.as_path(), Cow::Borrowed(methods_rs))];
+        for source in &self.additional_sources {
+            let source_rs =
+                fs::read_to_string(source).map_err(|e| format!("{}: {e}", source.display()))?;
+            sources.push((source.as_path(), Cow::Owned(source_rs)));
+        }
+        Ok(sources)
+    }
+
+    /// Returns a human-readable description of the methods that would be generated.
+    ///
+    /// For each method this lists its parameters with the resolved schema type and
+    /// requiredness, its result type, and its flags. This is meant for investigating
+    /// why a method generated the way it did, e.g. by printing it from a build script.
+    pub fn describe_methods(&self) -> Result<String, Box<dyn Error>> {
+        let mut description = String::new();
+        for method in self.methods()? {
+            description.push_str(&method.command);
+            if !method.aliases.is_empty() {
+                description.push_str(&format!(" (aliases: {})", method.aliases.join(", ")));
+            }
+            if method.deprecated {
+                description.push_str(" [deprecated]");
+            }
+            if method.item.is_some() {
+                description.push_str(" [subscription]");
+            }
+            let name = self.documented_name(&method.command);
+            if name != method.command {
+                description.push_str(&format!(" (documented as `{name}`)"));
+            }
+            description.push_str(&format!(" in trait `{}`\n", method.trait_name));
+
+            for param in &method.params {
+                let (schema_ty, format) = self.param_schema_type(param);
+                let required = match param.required {
+                    Some(true) => "required".into(),
+                    Some(false) => "optional".into(),
+                    None => format!(
+                        "required per `{}`",
+                        self.const_naming.required_const(&param.name)
+                    ),
+                };
+                description.push_str(&format!(
+                    "    param `{}`: {schema_ty}",
+                    param.documented_name()
+                ));
+                if let Some(format) = format {
+                    description.push_str(&format!(" (format: {format})"));
                 }
-                .expect("required")
-                .ident
-                .to_string();
-
-                let params = method.sig.inputs.iter().filter_map(|arg| match arg {
-                    syn::FnArg::Receiver(_) => None,
-                    syn::FnArg::Typed(pat_type) => match pat_type.pat.as_ref() {
-                        syn::Pat::Ident(pat_ident) => {
-                            let parameter = pat_ident.ident.to_string();
-                            let rust_ty = pat_type.ty.as_ref();
-
-                            // If we can determine the parameter's optionality, do so.
-                            let (param_ty, required) = match rust_ty {
-                                syn::Type::Path(type_path) => {
-                                    let is_standalone_ident =
-                                        type_path.path.leading_colon.is_none()
-                                            && type_path.path.segments.len() == 1;
-                                    let first_segment = &type_path.path.segments[0];
-
-                                    if first_segment.ident == "Option" && is_standalone_ident {
-                                        // Strip the `Option<_>` for the schema type.
-                                        let schema_ty = match &first_segment.arguments {
-                                            syn::PathArguments::AngleBracketed(args) => {
-                                                match args.args.first().expect("valid Option") {
-                                                    syn::GenericArgument::Type(ty) => ty,
-                                                    _ => panic!("Invalid Option"),
-                                                }
-                                            }
-                                            _ => panic!("Invalid Option"),
-                                        };
-                                        (schema_ty, Some(false))
-                                    } else if first_segment.ident == "Vec" {
-                                        // We don't know whether the vec may be empty.
-                                        (rust_ty, None)
-                                    } else {
-                                        (rust_ty, Some(true))
-                                    }
-                                }
-                                _ => (rust_ty, Some(true)),
-                            };
-
-                            // Handle a few conversions we know we need.
-                            let param_ty = param_ty.to_token_stream().to_string();
-                            let schema_ty = match param_ty.as_str() {
-                                "age :: secrecy :: SecretString" => "String".into(),
-                                _ => param_ty,
-                            };
-
-                            Some((parameter, schema_ty, required))
-                        }
-                        _ => None,
-                    },
-                });
+                description.push_str(&format!(", {required}"));
+                if param.deprecated {
+                    description.push_str(", deprecated");
+                }
+                description.push('\n');
+            }
+
+            match &method.item {
+                Some(item) => {
+                    description.push_str(&format!("    item: {}", self.schema_type(item).0))
+                }
+                None => match &method.result_schema_ty {
+                    Some(schema_ty) => description.push_str(&format!(
+                        "    result: {} (returns {})",
+                        self.schema_type(schema_ty).0,
+                        method.result_ty
+                    )),
+                    None => description.push_str(&format!("    result: {}", method.result_ty)),
+                },
+            }
+            if method.result_nullable {
+                description.push_str(", nullable");
+            }
+            description.push('\n');
+        }
+        Ok(description)
+    }
+
+    /// Resolves the type used for the schema of `rust_ty`, and the format it should
+    /// be documented with.
+    fn schema_type<'a>(&'a self, rust_ty: &'a str) -> (Cow<'a, str>, Option<&'a str>) {
+        if let Some(schema) = self.type_overrides.get(&normalize_type(rust_ty)) {
+            return (Cow::Borrowed(&schema.ty), schema.format.as_deref());
+        }
+        if let Ok(mut ty) = syn::parse_str::<syn::Type>(rust_ty)
+            && self.override_nested(&mut ty)
+        {
+            return (Cow::Owned(ty.to_token_stream().to_string()), None);
+        }
+        (Cow::Borrowed(rust_ty), None)
+    }
+
+    /// Resolves the type used for the schema of `param`, and the format it should be
+    /// documented with, preferring those given by its attributes.
+    fn param_schema_type<'a>(&'a self, param: &'a ParamInfo) -> (Cow<'a, str>, Option<&'a str>) {
+        let (schema_ty, format) = match &param.schema {
+            Some(schema_ty) => (Cow::Borrowed(schema_ty.as_str()), None),
+            None => self.schema_type(&param.schema_ty),
+        };
+        (schema_ty, param.format.as_deref().or(format))
+    }
+
+    /// Returns the note of the override applying to `rust_ty`, if any.
+    fn override_note(&self, rust_ty: &str) -> Option<&'static str> {
+        self.type_overrides.get(&normalize_type(rust_ty))?.note
+    }
 
-                contents.push('"');
-                contents.push_str(&command);
-                contents.push_str("\" => openrpsee::openrpc::RpcMethod {\n");
-
-                contents.push_str("    description: \"");
-                for attr in method
-                    .attrs
-                    .iter()
-                    .filter(|attr| attr.path().is_ident("doc"))
-                {
-                    if let syn::Meta::NameValue(doc_line) = &attr.meta {
-                        if let syn::Expr::Lit(docs) = &doc_line.value {
-                            if let syn::Lit::Str(s) = &docs.lit {
-                                // Trim the leading space from the doc comment line.
-                                let line = s.value();
-                                let trimmed_line = if line.is_empty() { &line } else { &line[1..] };
-
-                                let escaped = trimmed_line.escape_default().collect::<String>();
-
-                                contents.push_str(&escaped);
-                                contents.push_str("\\n");
+    /// Replaces the types within `ty` that have an override without a format (e.g. the
+    /// `T` of `Vec<T>`), returning whether any was replaced.
+    fn override_nested(&self, ty: &mut syn::Type) -> bool {
+        if let Some(schema) = self
+            .type_overrides
+            .get(&normalize_type(&ty.to_token_stream().to_string()))
+            && schema.format.is_none()
+            && let Ok(replacement) = syn::parse_str(&schema.ty)
+        {
+            *ty = replacement;
+            return true;
+        }
+        match ty {
+            syn::Type::Path(type_path) => {
+                let mut replaced = false;
+                for segment in &mut type_path.path.segments {
+                    if let syn::PathArguments::AngleBracketed(args) = &mut segment.arguments {
+                        for arg in &mut args.args {
+                            if let syn::GenericArgument::Type(ty) = arg {
+                                replaced |= self.override_nested(ty);
                             }
                         }
                     }
                 }
-                contents.push_str("\",\n");
-
-                contents.push_str("    params: |_g| vec![\n");
-                for (parameter, schema_ty, required) in params {
-                    let param_upper = parameter.to_uppercase();
-
-                    contents.push_str("        _g.param::<");
-                    contents.push_str(&schema_ty);
-                    contents.push_str(">(\"");
-                    contents.push_str(&parameter);
-                    if use_parent_module {
-                        contents.push_str("\", super::");
-                        contents.push_str(&module);
-                    } else {
-                        contents.push_str("\", crate::methods");
-                    }
-                    contents.push_str("::PARAM_");
-                    contents.push_str(&param_upper);
-                    contents.push_str("_DESC, ");
-                    match required {
-                        Some(required) => contents.push_str(&required.to_string()),
-                        None => {
-                            // Require a helper const to be present.
-                            if use_parent_module {
-                                contents.push_str("super::");
-                            } else {
-                                contents.push_str("crate::methods::");
-                            }
-                            contents.push_str(&module);
-                            contents.push_str("::PARAM_");
-                            contents.push_str(&param_upper);
-                            contents.push_str("_REQUIRED");
+                replaced
+            }
+            syn::Type::Tuple(tuple) => tuple
+                .elems
+                .iter_mut()
+                .fold(false, |replaced, ty| self.override_nested(ty) | replaced),
+            syn::Type::Array(array) => self.override_nested(&mut array.elem),
+            syn::Type::Slice(slice) => self.override_nested(&mut slice.elem),
+            syn::Type::Reference(reference) => self.override_nested(&mut reference.elem),
+            syn::Type::Paren(paren) => self.override_nested(&mut paren.elem),
+            _ => false,
+        }
+    }
+
+    /// Extracts the methods to document from the given source files, adding the build
+    /// warnings to `warnings`.
+    fn parse_methods(
+        &self,
+        sources: &[Source<'_>],
+        warnings: &mut Vec<String>,
+    ) -> Result<Vec<MethodInfo>, Box<dyn Error>> {
+        let mut methods = parse::parse_methods(self, sources, warnings)?;
+        self.default_required(&mut methods, sources, warnings)?;
+        Ok(methods)
+    }
+
+    /// Reports parameters whose requiredness is neither stated nor known from their
+    /// type, if the source files show that their `required` constant isn't defined, as
+    /// set by [`GenerateOptions::missing_required`]. Unless that fails, they are
+    /// documented as required.
+    ///
+    /// The constant is looked for in the module named after the method's return type,
+    /// declared in one of the source files. Modules in files of their own aren't read,
+    /// so their constants are still referred to.
+    fn default_required(
+        &self,
+        methods: &mut [MethodInfo],
+        sources: &[Source<'_>],
+        warnings: &mut Vec<String>,
+    ) -> Result<(), Box<dyn Error>> {
+        let asts = sources
+            .iter()
+            .map(|(_, source_rs)| syn::parse_file(source_rs))
+            .collect::<Result<Vec<_>, _>>()?;
+        for method in methods {
+            for param in method
+                .params
+                .iter_mut()
+                .filter(|param| param.required.is_none())
+            {
+                let name = self.const_naming.required_const(&param.name);
+                let defined = |ast: &syn::File| match method.module.as_str() {
+                    "" => parse::defines_const(ast, &name),
+                    module => parse::may_define_const(ast, module, &name),
+                };
+                if !asts.iter().any(defined) {
+                    let path = match method.module.as_str() {
+                        "" => name.clone(),
+                        module => format!("{module}::{name}"),
+                    };
+                    match self.missing_required {
+                        DocCheck::Deny => {
+                            return Err(format!(
+                                "{}: method '{}': whether parameter '{}' is required isn't \
+                                 known, as neither `#[openrpc(param(name = \"{}\", required \
+                                 = ...))]` nor `{path}` is defined",
+                                self.source_name(),
+                                method.command,
+                                param.name,
+                                param.name,
+                            )
+                            .into());
                         }
+                        DocCheck::Warn => warnings.push(format!(
+                            "method '{}': parameter '{}' is documented as required, as \
+                             neither `#[openrpc(param(name = \"{}\", required = ...))]` nor \
+                             `{path}` is defined",
+                            method.command, param.name, param.name,
+                        )),
+                        DocCheck::Allow => {}
                     }
-                    contents.push_str("),\n");
+                    param.required = Some(true);
                 }
-                contents.push_str("    ],\n");
-
-                contents.push_str("    result: |g| g.result::<openrpsee::openrpc");
-                contents.push_str("::ResultType>(\"");
-                contents.push_str(&command);
-                contents.push_str("_result\"),\n");
-
-                contents.push_str("    deprecated: ");
-                contents.push_str(
-                    &method
-                        .attrs
-                        .iter()
-                        .any(|attr| attr.path().is_ident("deprecated"))
-                        .to_string(),
-                );
-                contents.push_str(",\n");
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds a warning to `warnings` for each parameter whose description constant is
+    /// defined empty in one of the source files.
+    fn warn_param_docs(
+        &self,
+        methods: &[MethodInfo],
+        sources: &[Source<'_>],
+        warnings: &mut Vec<String>,
+    ) -> Result<(), Box<dyn Error>> {
+        let asts = sources
+            .iter()
+            .map(|(_, source_rs)| syn::parse_file(source_rs))
+            .collect::<Result<Vec<_>, _>>()?;
+        for method in methods {
+            // Constants in another module than the source file's are looked up at its
+            // top level, as the source file is usually that module.
+            let module = match &self.const_module {
+                ConstModule::Parent => Some(method.module.as_str()).filter(|m| !m.is_empty()),
+                ConstModule::Path(_) => None,
+            };
+            for param in &method.params {
+                let name = self.const_naming.desc_const(&param.name);
+                if asts.iter().any(|ast| {
+                    parse::module_str_const(ast, module, &name)
+                        .is_some_and(|description| description.trim().is_empty())
+                }) {
+                    warnings.push(format!(
+                        "method '{}': parameter '{}' has no description (`{name}` is empty)",
+                        method.command, param.name,
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the name a method is documented under, with
+    /// [`GenerateOptions::strip_method_prefix`] and
+    /// [`GenerateOptions::method_name_case`] applied.
+    fn documented_name(&self, name: &str) -> String {
+        let name = self
+            .strip_method_prefix
+            .as_deref()
+            .and_then(|prefix| name.strip_prefix(prefix))
+            .filter(|stripped| !stripped.is_empty())
+            .unwrap_or(name);
+        match self.method_name_case {
+            NameCase::AsIs => name.to_string(),
+            NameCase::CamelCase => name.to_lower_camel_case(),
+            NameCase::SnakeCase => name.to_snake_case(),
+        }
+    }
+
+    /// The overlay file next to the source file, e.g. `wallet.openrpc.json` for
+    /// `wallet.rs`.
+    fn overlay_path(&self) -> PathBuf {
+        self.source.with_extension(OVERLAY_EXTENSION)
+    }
+
+    /// Reads the overlay file, if there is one, checking that each method it names is
+    /// documented.
+    fn read_overlay(&self, methods: &[MethodInfo]) -> Result<Overlay, Box<dyn Error>> {
+        let path = self.overlay_path();
+        if !path.is_file() {
+            return Ok(Overlay::default());
+        }
+        let file = path.display();
+
+        let mut document = match serde_json::from_str(&fs::read_to_string(&path)?) {
+            Ok(serde_json::Value::Object(document)) => document,
+            Ok(_) => return Err(format!("{file}: expected a JSON object").into()),
+            Err(e) => return Err(format!("{file}: invalid JSON: {e}").into()),
+        };
+        let methods_overlay = match document.remove("methods") {
+            None => serde_json::Map::new(),
+            Some(serde_json::Value::Object(methods_overlay)) => methods_overlay,
+            Some(_) => return Err(format!("{file}: `methods` must be a JSON object").into()),
+        };
 
-                contents.push_str("},\n");
+        let mut overlay = Overlay {
+            document: serde_json::Value::Object(document).to_string(),
+            methods: BTreeMap::new(),
+        };
+        for (name, method_overlay) in methods_overlay {
+            if !method_overlay.is_object() {
+                return Err(
+                    format!("{file}: overlay of method `{name}` must be a JSON object").into(),
+                );
+            }
+            if !methods.iter().any(|method| method.command == name) {
+                return Err(format!("{file}: `{name}` is not a documented method").into());
             }
+            overlay.methods.insert(name, method_overlay.to_string());
+        }
+        Ok(overlay)
+    }
+
+    fn source_name(&self) -> String {
+        self.source.display().to_string()
+    }
+
+    fn trait_names(&self) -> Vec<&str> {
+        self.trait_names.iter().map(String::as_str).collect()
+    }
+}
+
+/// A schema used in place of a Rust type's own, registered with
+/// [`GenerateOptions::type_override`].
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct SchemaOverride {
+    ty: String,
+    format: Option<String>,
+    /// Why the type is documented this way, noted in the generated file.
+    note: Option<&'static str>,
+}
+
+impl SchemaOverride {
+    /// Documents the type with the schema of `ty`, which must implement `JsonSchema`
+    /// in the scope of the generated file.
+    pub fn new(ty: &str) -> Self {
+        Self {
+            ty: ty.into(),
+            format: None,
+            note: None,
         }
     }
 
-    contents.push_str("};");
+    /// Sets the `format` keyword of the schema.
+    pub fn with_format(mut self, format: &str) -> Self {
+        self.format = Some(format.into());
+        self
+    }
+
+    /// Documents the type as a hex-encoded byte string.
+    pub fn hex_bytes() -> Self {
+        Self::new("String").with_format("hex")
+    }
 
-    let rpc_openrpc_path = out_dir.join("rpc_openrpc.rs");
-    fs::write(&rpc_openrpc_path, contents)?;
+    /// Documents the type as a base64-encoded byte string.
+    pub fn base64_bytes() -> Self {
+        Self::new("String").with_format("base64")
+    }
+
+    /// Documents a signed 128-bit integer type as a decimal string, with an `int128`
+    /// format.
+    ///
+    /// Many JSON parsers, such as JavaScript's, read numbers as 64-bit floats, which
+    /// can't represent every 128-bit integer, so such integers are best serialized as
+    /// strings (e.g. with `serde_with::DisplayFromStr`) and documented as such.
+    pub fn i128_string() -> Self {
+        Self {
+            note: Some(INT128_NOTE),
+            ..Self::new("String").with_format("int128")
+        }
+    }
+
+    /// Documents an unsigned 128-bit integer type as a decimal string, with a
+    /// `uint128` format; see [`SchemaOverride::i128_string`].
+    pub fn u128_string() -> Self {
+        Self {
+            note: Some(INT128_NOTE),
+            ..Self::new("String").with_format("uint128")
+        }
+    }
+}
+
+/// Removes whitespace from a type, so that types can be compared regardless of how
+/// they were formatted.
+fn normalize_type(ty: &str) -> String {
+    ty.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+/// The kind of map the generated lookup table is.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub enum MapKind {
+    /// A `phf::Map`, built at compile time. The consuming crate needs a `phf`
+    /// dependency with the `macros` feature.
+    #[default]
+    Phf,
+    /// A `once_cell::sync::Lazy<HashMap<&str, RpcMethod>>`, built on first use. The
+    /// consuming crate needs a `once_cell` dependency.
+    LazyHashMap,
+    /// A `methods()` function returning a new `HashMap<&str, RpcMethod>` on each call,
+    /// e.g. to add or remove methods at runtime. No further dependency is needed.
+    HashMapFn,
+}
+
+/// How missing documentation is reported, e.g. by [`GenerateOptions::param_docs`].
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub enum DocCheck {
+    /// Missing documentation isn't reported.
+    #[default]
+    Allow,
+    /// Missing documentation is reported as a `cargo:warning`.
+    Warn,
+    /// Missing documentation fails the build.
+    Deny,
+}
+
+/// The module in which the generated code looks up the per-parameter constants.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum ConstModule {
+    /// Description constants are looked up in the given module, and requiredness
+    /// constants in a submodule of it named after the first path segment of each
+    /// method's return type.
+    Path(String),
+    /// Both kinds of constants are looked up in `super::<module>`, where `<module>` is
+    /// the first path segment of each method's return type.
+    Parent,
+}
+
+impl Default for ConstModule {
+    fn default() -> Self {
+        Self::Path("crate::methods".into())
+    }
+}
+
+/// How the names of the per-parameter constants are derived from a parameter's
+/// identifier.
+///
+/// A constant is named `<prefix><transformed identifier><suffix>`, where the suffix
+/// is [`ConstNaming::desc_suffix`] for the description constant and
+/// [`ConstNaming::required_suffix`] for the optional requiredness constant. The
+/// default produces `PARAM_<IDENT>_DESC` and `PARAM_<IDENT>_REQUIRED`.
+#[derive(Clone, Debug, Hash)]
+pub struct ConstNaming {
+    /// Prepended to the transformed identifier.
+    pub prefix: String,
+    /// Appended to the transformed identifier for description constants.
+    pub desc_suffix: String,
+    /// Appended to the transformed identifier for requiredness constants.
+    pub required_suffix: String,
+    /// How the identifier is transformed.
+    pub case: ConstCase,
+}
+
+impl Default for ConstNaming {
+    fn default() -> Self {
+        Self {
+            prefix: "PARAM_".into(),
+            desc_suffix: "_DESC".into(),
+            required_suffix: "_REQUIRED".into(),
+            case: ConstCase::Upper,
+        }
+    }
+}
+
+impl ConstNaming {
+    fn desc_const(&self, parameter: &str) -> String {
+        self.const_name(parameter, &self.desc_suffix)
+    }
+
+    fn required_const(&self, parameter: &str) -> String {
+        self.const_name(parameter, &self.required_suffix)
+    }
+
+    fn const_name(&self, parameter: &str, suffix: &str) -> String {
+        let ident = match self.case {
+            ConstCase::Upper => parameter.to_uppercase(),
+            ConstCase::ShoutySnake => parameter.to_shouty_snake_case(),
+            ConstCase::AsIs => parameter.to_string(),
+        };
+        format!("{}{ident}{suffix}", self.prefix)
+    }
+}
+
+/// How a parameter's identifier is transformed into part of a constant's name.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub enum ConstCase {
+    /// Uppercases the identifier (`block_hash` → `BLOCK_HASH`, `blockHash` →
+    /// `BLOCKHASH`).
+    #[default]
+    Upper,
+    /// Converts the identifier to screaming snake case, splitting on word
+    /// boundaries (`blockHash` → `BLOCK_HASH`).
+    ShoutySnake,
+    /// Uses the identifier unchanged.
+    AsIs,
+}
+
+/// The naming convention methods are documented under, set with
+/// [`GenerateOptions::method_name_case`].
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub enum NameCase {
+    /// Uses the names unchanged.
+    #[default]
+    AsIs,
+    /// Converts names to lower camel case (`get_block_hash` → `getBlockHash`).
+    CamelCase,
+    /// Converts names to snake case (`getBlockHash` → `get_block_hash`).
+    SnakeCase,
+}
+
+/// Hand-authored fields merged over the generated output, read from the file next to
+/// the source with [`OVERLAY_EXTENSION`].
+#[derive(Debug)]
+pub(crate) struct Overlay {
+    /// The top-level document fields, as a JSON object.
+    pub(crate) document: String,
+    /// The fields of each method, as JSON objects, by method name.
+    pub(crate) methods: BTreeMap<String, String>,
+}
+
+impl Default for Overlay {
+    fn default() -> Self {
+        Self {
+            document: "{}".into(),
+            methods: BTreeMap::new(),
+        }
+    }
+}
+
+/// The extension of the overlay file, which replaces that of the source file.
+const OVERLAY_EXTENSION: &str = "openrpc.json";
+
+/// The type overrides registered by [`GenerateOptions::new`], as `(Rust type, schema
+/// type)` pairs.
+const DEFAULT_TYPE_OVERRIDES: &[(&str, &str)] = &[("age::secrecy::SecretString", "String")];
+
+/// Why 128-bit integers are documented as strings, noted in the generated file.
+const INT128_NOTE: &str =
+    "documented as a decimal string, as JSON numbers can't represent every 128-bit integer exactly";
+
+/// The name of the generated file within the output directory.
+const OUTPUT_FILE: &str = "rpc_openrpc.rs";
+
+/// The name of the file written by [`GenerateOptions::param_desc_stubs`], next to the
+/// lookup table.
+const PARAM_DESC_STUBS_FILE: &str = "rpc_param_descs.rs";
+
+/// The name used for sources passed to [`generate_rpc_openrpc_from_str`] in error
+/// messages.
+const SOURCE_STR_NAME: &str = "<source>";
+
+/// Tells Cargo to rerun the build script when `path` changes.
+fn emit_rerun_if_changed(path: &Path) {
+    println!("cargo:rerun-if-changed={}", path.display());
+}
+
+/// A source file, along with its contents.
+type Source<'a> = (&'a Path, Cow<'a, str>);
+
+/// The extension of the fingerprint file written when [`GenerateOptions::cache`] is
+/// set, which replaces that of the generated file.
+const FINGERPRINT_EXTENSION: &str = "fingerprint";
+
+/// Reports each of `warnings` to Cargo as a `cargo:warning`.
+fn emit_warnings<'a>(warnings: impl IntoIterator<Item = &'a str>) {
+    for warning in warnings {
+        println!("cargo:warning={warning}");
+    }
+}
+
+/// A 64-bit FNV-1a hasher, whose output, unlike that of `std`'s `DefaultHasher`, stays
+/// the same across Rust releases, so that fingerprints do too.
+struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl StableHasher {
+    /// Adds `bytes` to the hash, preceded by their length, so that consecutive parts
+    /// can't run into each other.
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in (bytes.len() as u64).to_le_bytes().iter().chain(bytes) {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Returns the Rust types used by the parameters and results of the methods in the
+/// given traits.
+///
+/// Each entry is the type as written in the source file, or the type it is documented
+/// as (such as `String` for `age::secrecy::SecretString`), deduplicated and sorted.
+/// This lets callers pre-register every schema a trait uses with a
+/// [`openrpc::Generator`], even for methods they don't include in their document.
+pub fn referenced_types(
+    json_rpc_methods_rs: &str,
+    trait_names: &[&str],
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let methods_rs = fs::read_to_string(json_rpc_methods_rs)?;
+    let options = GenerateOptions::new(json_rpc_methods_rs, trait_names, Path::new(""))
+        .include_doc_hidden(true);
+    let mut warnings = vec![];
+    let methods = parse::parse_methods(&options, &options.sources(&methods_rs)?, &mut warnings);
+    emit_warnings(warnings.iter().map(String::as_str));
+    let methods = methods?;
+
+    // Types are documented with the schema of their override, as in the lookup table.
+    let types: BTreeSet<String> = methods
+        .iter()
+        .flat_map(|method| {
+            let result_ty = match method.item.as_ref().or(method.result_schema_ty.as_ref()) {
+                Some(schema_ty) => options.schema_type(schema_ty).0,
+                None => Cow::Borrowed(method.result_ty.as_str()),
+            };
+            method
+                .params
+                .iter()
+                .map(|param| options.param_schema_type(param).0)
+                .chain(Some(result_ty))
+                .map(Cow::into_owned)
+        })
+        .collect();
 
-    Ok(())
+    Ok(types.into_iter().collect())
 }
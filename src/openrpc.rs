@@ -1,27 +1,89 @@
 //! OpenRPC document generation for JSON-RPC methods.
 
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    ffi::OsString,
+    fs, io,
+    path::Path,
+    sync::OnceLock,
+};
 
 use documented::Documented;
 use jsonrpsee::core::{JsonValue, RpcResult};
 use schemars::{JsonSchema, Schema, SchemaGenerator, generate::SchemaSettings};
-use serde::Serialize;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de, ser::SerializeMap};
+use serde_json::Map as JsonMap;
+
+#[cfg(feature = "codegen")]
+pub mod codegen;
+mod diff;
+#[cfg(feature = "render")]
+pub mod render;
+#[cfg(test)]
+mod tests;
+#[cfg(feature = "validate")]
+pub mod validate;
+
+pub use diff::{Change, ChangeKind, DiffReport, diff};
 
 /// Response to an `rpc.discover` RPC request.
 pub type Response = RpcResult<ResultType>;
 /// The result type for an `rpc.discover` RPC request.
 pub type ResultType = OpenRpc;
 
+/// The OpenRPC specification version of documents assembled by [`OpenRpc::build`].
+pub const OPENRPC_VERSION: &str = "1.3.2";
+
 /// Static information about a Zallet JSON-RPC method.
 pub struct RpcMethod {
+    /// Whether the method is compiled into this build.
+    ///
+    /// Methods gated behind a `cfg` that is disabled keep their entry, but with
+    /// placeholder parameters and result; leave them out of generated documents.
+    pub available: bool,
+    /// Alternative names the method can also be called by.
+    pub aliases: &'static [&'static str],
+    /// The names of the tags the method is grouped under.
+    pub tags: &'static [&'static str],
     /// A description of the method.
     pub description: &'static str,
+    /// A short summary of the method, if it isn't the first line of its description.
+    pub summary: Option<&'static str>,
     /// A function that generates the method's parameters.
     pub params: fn(&mut Generator) -> Vec<ContentDescriptor>,
     /// A function that generates the method's result.
     pub result: fn(&mut Generator) -> ContentDescriptor,
     /// Whether the method is deprecated.
     pub deprecated: bool,
+    /// The name of the method that replaces this deprecated one, if any.
+    pub replaced_by: Option<&'static str>,
+    /// The reason the method is deprecated, if given.
+    pub deprecation_note: Option<&'static str>,
+    /// Whether the method is a subscription.
+    ///
+    /// For subscriptions, [`RpcMethod::result`] describes a single notification item.
+    pub subscription: bool,
+    /// The name of the method that cancels the subscription, if known.
+    pub unsubscribe: Option<&'static str>,
+    /// Hints about the cost of calling the method, if documented.
+    pub cost: Option<Cost>,
+    /// Named examples of the method's result, as `(name, JSON value)` pairs.
+    pub result_examples: &'static [(&'static str, &'static str)],
+    /// Named examples of calls to the method.
+    pub examples: &'static [MethodExample],
+    /// The errors the method is documented to return.
+    pub errors: &'static [MethodError],
+    /// How the method accepts its parameters.
+    pub param_structure: ParamStructure,
+    /// The name the method is documented under, if it differs from its real name.
+    ///
+    /// The real name is then preserved in the method's `x-internal-name` extension.
+    pub public_name: Option<&'static str>,
+    /// Fields merged over the generated method, as a JSON object, if any.
+    ///
+    /// Each field replaces the generated field of the same name.
+    pub overlay: Option<&'static str>,
 }
 
 impl RpcMethod {
@@ -30,15 +92,134 @@ impl RpcMethod {
         let description = self.description.trim();
 
         Method {
-            name,
-            summary: description
-                .split_once('\n')
-                .map(|(summary, _)| summary)
-                .unwrap_or(description),
-            description,
+            name: self.public_name.unwrap_or(name).into(),
+            internal_name: self.public_name.is_some().then_some(name.into()),
+            aliases: self.aliases.iter().map(|&alias| alias.into()).collect(),
+            tags: self.tags.iter().map(|&name| generator.tag(name)).collect(),
+            summary: self
+                .summary
+                .unwrap_or_else(|| first_line(description))
+                .into(),
+            description: description.into(),
             params: (self.params)(generator),
             result: (self.result)(generator),
             deprecated: self.deprecated,
+            replaced_by: self.replaced_by.map(Cow::Borrowed),
+            deprecation_note: self.deprecation_note.map(Cow::Borrowed),
+            subscription: self.subscription,
+            unsubscribe: self.unsubscribe.map(Cow::Borrowed),
+            cost: self.cost,
+            examples: self
+                .result_examples
+                .iter()
+                .map(|&(name, value)| ExamplePairing {
+                    name: name.into(),
+                    params: vec![],
+                    result: Example {
+                        name: name.into(),
+                        value: serde_json::from_str(value)
+                            .expect("result examples are checked to be JSON when generated"),
+                    },
+                })
+                .chain(self.examples.iter().map(MethodExample::pairing))
+                .collect(),
+            errors: self
+                .errors
+                .iter()
+                .map(|error| generator.error(error))
+                .collect(),
+            param_structure: Some(self.param_structure),
+            overlay: self.overlay.map_or_else(JsonMap::new, |overlay| {
+                serde_json::from_str(overlay)
+                    .expect("method overlays are checked to be JSON objects when generated")
+            }),
+        }
+    }
+}
+
+/// The JSON Schema draft that generated schemas follow.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SchemaDraft {
+    /// JSON Schema draft 7, which the OpenRPC specification is written against.
+    #[default]
+    Draft07,
+    /// JSON Schema 2020-12, for client tooling that only understands that draft.
+    Draft2020_12,
+}
+
+/// Configures a [`Generator`], produced by [`Generator::builder`].
+#[derive(Clone, Debug)]
+pub struct GeneratorBuilder {
+    draft: SchemaDraft,
+    definitions_path: String,
+    schema_titles: bool,
+    response_envelope: bool,
+}
+
+impl Default for GeneratorBuilder {
+    fn default() -> Self {
+        Self {
+            draft: SchemaDraft::default(),
+            definitions_path: COMPONENT_REF_PREFIX.into(),
+            schema_titles: false,
+            response_envelope: false,
+        }
+    }
+}
+
+impl GeneratorBuilder {
+    /// Sets the JSON Schema draft that generated schemas follow.
+    ///
+    /// Defaults to [`SchemaDraft::Draft07`].
+    pub fn draft(mut self, draft: SchemaDraft) -> Self {
+        self.draft = draft;
+        self
+    }
+
+    /// Sets the path that references to named schemas point into.
+    ///
+    /// Defaults to `#/components/schemas/`. The schemas are still returned as the
+    /// document's `components.schemas`, so another path is only useful when the
+    /// document is rearranged afterwards: [`OpenRpc::inline_small_schemas`] and
+    /// validation only follow references into `components.schemas`.
+    pub fn definitions_path(mut self, definitions_path: impl Into<String>) -> Self {
+        self.definitions_path = definitions_path.into();
+        self
+    }
+
+    /// Sets whether named schemas without a `title` are given their name as one.
+    ///
+    /// Viewers show the title of a schema where it is used, rather than only the key
+    /// of its component. Defaults to `false`.
+    pub fn schema_titles(mut self, schema_titles: bool) -> Self {
+        self.schema_titles = schema_titles;
+        self
+    }
+
+    /// Sets whether the components include the schemas of the JSON-RPC 2.0 response
+    /// envelope, as added by [`Generator::add_response_envelope`].
+    ///
+    /// They are added each time components are produced, e.g. by
+    /// [`OpenRpc::build_with`]. Defaults to `false`.
+    pub fn response_envelope(mut self, response_envelope: bool) -> Self {
+        self.response_envelope = response_envelope;
+        self
+    }
+
+    /// Produces the configured generator.
+    pub fn build(self) -> Generator {
+        let settings = match self.draft {
+            SchemaDraft::Draft07 => SchemaSettings::draft07(),
+            SchemaDraft::Draft2020_12 => SchemaSettings::draft2020_12(),
+        };
+        Generator {
+            inner: settings
+                .with(|s| s.definitions_path = self.definitions_path.into())
+                .into_generator(),
+            schema_titles: self.schema_titles,
+            response_envelope: self.response_envelope,
+            tags: BTreeMap::new(),
+            errors: JsonMap::new(),
         }
     }
 }
@@ -46,18 +227,30 @@ impl RpcMethod {
 /// An OpenRPC document generator.
 pub struct Generator {
     inner: SchemaGenerator,
+    schema_titles: bool,
+    response_envelope: bool,
+    tags: BTreeMap<String, Tag>,
+    errors: JsonMap<String, JsonValue>,
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Generator {
     /// Creates a new OpenRPC document generator.
+    ///
+    /// Schemas follow JSON Schema draft 7 and are placed in `components.schemas`; use
+    /// [`Generator::builder`] to change either.
     pub fn new() -> Self {
-        Self {
-            inner: SchemaSettings::draft07()
-                .with(|s| {
-                    s.definitions_path = "#/components/schemas/".into();
-                })
-                .into_generator(),
-        }
+        Self::builder().build()
+    }
+
+    /// Starts configuring an OpenRPC document generator.
+    pub fn builder() -> GeneratorBuilder {
+        GeneratorBuilder::default()
     }
 
     /// Constructs the descriptor for a JSON-RPC method parameter.
@@ -67,53 +260,635 @@ impl Generator {
         description: &'static str,
         required: bool,
     ) -> ContentDescriptor {
-        ContentDescriptor {
-            name,
-            summary: description
-                .split_once('\n')
-                .map(|(summary, _)| summary)
-                .unwrap_or(description),
-            description,
-            required,
-            schema: self.inner.subschema_for::<T>(),
-            deprecated: false,
-        }
+        ContentDescriptor::new(name, description, required, self.subschema_for::<T>())
+    }
+
+    /// Constructs the descriptor for a JSON-RPC method parameter that has a default
+    /// value.
+    ///
+    /// The default is serialized into the `default` keyword of the parameter's schema.
+    pub fn param_with_default<T: JsonSchema>(
+        &mut self,
+        name: &'static str,
+        description: &'static str,
+        required: bool,
+        default: &impl Serialize,
+    ) -> ContentDescriptor {
+        let mut descriptor = self.param::<T>(name, description, required);
+        descriptor.schema.insert(
+            "default".into(),
+            serde_json::to_value(default).expect("default value can be serialized to JSON"),
+        );
+        descriptor
+    }
+
+    /// Constructs the descriptor for a JSON-RPC method parameter along with an
+    /// example value, e.g. a concrete address for an opaque string type.
+    ///
+    /// The example is serialized into the parameter's `schema.examples` array. The
+    /// descriptor gets no `example` field of its own: the OpenRPC meta-schema
+    /// doesn't allow additional properties on content descriptors.
+    pub fn param_with_example<T: JsonSchema + Serialize>(
+        &mut self,
+        name: &'static str,
+        description: &'static str,
+        required: bool,
+        example: &T,
+    ) -> ContentDescriptor {
+        let mut descriptor = self.param::<T>(name, description, required);
+        descriptor.schema.insert(
+            "examples".into(),
+            JsonValue::Array(vec![
+                serde_json::to_value(example).expect("example value can be serialized to JSON"),
+            ]),
+        );
+        descriptor
     }
 
     /// Constructs the descriptor for a JSON-RPC method's result type.
     pub fn result<T: Documented + JsonSchema>(&mut self, name: &'static str) -> ContentDescriptor {
-        ContentDescriptor {
-            name,
-            summary: T::DOCS
-                .split_once('\n')
-                .map(|(summary, _)| summary)
-                .unwrap_or(T::DOCS),
-            description: T::DOCS,
-            required: false,
-            schema: self.inner.subschema_for::<T>(),
-            deprecated: false,
-        }
+        ContentDescriptor::new(name, T::DOCS, false, self.subschema_for::<T>())
+    }
+
+    /// Constructs the descriptor for a JSON-RPC method's result type, described by
+    /// its schema's description (e.g. the doc comment of a derived `JsonSchema`).
+    ///
+    /// Unlike [`Generator::result`], this doesn't require `T` to be [`Documented`].
+    pub fn result_of<T: JsonSchema>(&mut self, name: &'static str) -> ContentDescriptor {
+        let description = T::json_schema(&mut self.inner)
+            .get("description")
+            .and_then(JsonValue::as_str)
+            .unwrap_or_default()
+            .to_owned();
+
+        ContentDescriptor::new(name, description, false, self.subschema_for::<T>())
+    }
+
+    /// Constructs the descriptor for the items produced by a JSON-RPC subscription.
+    pub fn item<T: JsonSchema>(&mut self, name: &'static str) -> ContentDescriptor {
+        let description = "An item sent in each notification of the subscription.";
+
+        ContentDescriptor::new(name, description, false, self.subschema_for::<T>())
+    }
+
+    /// Adds the schemas of the JSON-RPC 2.0 response envelope to the components.
+    ///
+    /// This adds `JsonRpcResponse`, a full response carrying either an arbitrary
+    /// `result` or an `error`, and `JsonRpcError`, the error object it references
+    /// under the generator's definitions path.
+    pub fn add_response_envelope(&mut self) {
+        let error_ref = self.schema_ref("JsonRpcError");
+        let definitions = self.inner.definitions_mut();
+        definitions.insert(
+            "JsonRpcError".into(),
+            serde_json::json!({
+                "description": "A JSON-RPC 2.0 error object.",
+                "type": "object",
+                "properties": {
+                    "code": { "type": "integer" },
+                    "message": { "type": "string" },
+                    "data": {},
+                },
+                "required": ["code", "message"],
+            }),
+        );
+        definitions.insert(
+            "JsonRpcResponse".into(),
+            serde_json::json!({
+                "description": "A JSON-RPC 2.0 response.",
+                "type": "object",
+                "properties": {
+                    "jsonrpc": { "const": "2.0" },
+                    "id": { "type": ["string", "integer", "null"] },
+                },
+                "required": ["jsonrpc", "id"],
+                "oneOf": [
+                    {
+                        "properties": { "result": {} },
+                        "required": ["result"],
+                        "not": { "required": ["error"] },
+                    },
+                    {
+                        "properties": { "error": { "$ref": error_ref } },
+                        "required": ["error"],
+                        "not": { "required": ["result"] },
+                    },
+                ],
+            }),
+        );
     }
 
     /// Consumes the generator and produces the OpenRPC components.
     pub fn into_components(mut self) -> Components {
+        self.take_components()
+    }
+
+    /// Produces the OpenRPC components generated so far, leaving the generator usable.
+    ///
+    /// The generator's definitions are emptied, so the next call only returns the
+    /// schemas referenced since this one, including any already returned here that are
+    /// referenced again; each type keeps the same schema name across calls. Schemas
+    /// added by [`Generator::add_response_envelope`] are taken too, and have to be added
+    /// again for the next document, unless the generator adds them itself (see
+    /// [`GeneratorBuilder::response_envelope`]).
+    pub fn take_components(&mut self) -> Components {
+        if self.response_envelope {
+            self.add_response_envelope();
+        }
+        let mut schemas: BTreeMap<_, _> = self.inner.take_definitions(true).into_iter().collect();
+        if self.schema_titles {
+            for (name, schema) in &mut schemas {
+                if let JsonValue::Object(schema) = schema {
+                    schema.entry("title").or_insert_with(|| name.clone().into());
+                }
+            }
+        }
         Components {
-            schemas: self.inner.take_definitions(false),
+            schemas,
+            content_descriptors: BTreeMap::new(),
+            tags: std::mem::take(&mut self.tags),
+            errors: std::mem::take(&mut self.errors),
+        }
+    }
+
+    /// Returns a reference to the named schema `name` under the definitions path.
+    fn schema_ref(&self, name: &str) -> String {
+        let path = &self.inner.settings().definitions_path;
+        let path = path.strip_prefix('#').unwrap_or(path);
+        format!("#{}/{name}", path.strip_suffix('/').unwrap_or(path))
+    }
+
+    /// Generates the schema of `T`, adjusted to the generator's JSON Schema draft.
+    fn subschema_for<T: JsonSchema>(&mut self) -> Schema {
+        let mut schema = self.inner.subschema_for::<T>();
+        for transform in self.inner.transforms_mut() {
+            transform.transform(&mut schema);
         }
+        schema
+    }
+
+    /// Adds the tag `name` to the components, returning it.
+    fn tag(&mut self, name: &'static str) -> Tag {
+        let tag = Tag { name: name.into() };
+        self.tags.entry(name.into()).or_insert_with(|| tag.clone());
+        tag
+    }
+
+    /// Adds `error` to the components, returning a reference to it.
+    fn error(&mut self, error: &MethodError) -> Reference {
+        self.errors.entry(error.name).or_insert_with(|| {
+            let mut object = JsonMap::new();
+            object.insert("code".into(), error.code.into());
+            object.insert("message".into(), error.message.into());
+            if let Some(data) = error.data {
+                object.insert(
+                    "data".into(),
+                    serde_json::from_str(data)
+                        .expect("error data is checked to be JSON when generated"),
+                );
+            }
+            object.into()
+        });
+        Reference {
+            reference: format!("#/components/errors/{}", error.name),
+        }
+    }
+}
+
+/// Generates the descriptors of a generated `METHODS` map (or any other collection of
+/// named [`RpcMethod`]s), e.g. to merge them with hand-written ones.
+///
+/// As in [`OpenRpc::build`], methods that aren't available in this build are left
+/// out, and the rest are sorted by their documented name. The schemas they refer to
+/// are collected in `generator`, e.g. for [`Generator::into_components`].
+pub fn generate_methods<'a>(
+    generator: &mut Generator,
+    methods: impl IntoIterator<Item = (&'a &'static str, &'a RpcMethod)>,
+) -> Vec<Method> {
+    let mut methods: Vec<_> = methods
+        .into_iter()
+        .filter(|(_, method)| method.available)
+        .collect();
+    methods.sort_by_key(|(name, _)| **name);
+    let mut methods: Vec<_> = methods
+        .into_iter()
+        .map(|(name, method)| method.generate(generator, name))
+        .collect();
+    // Methods may be documented under other names than they are keyed by.
+    methods.sort_by(|a, b| a.name.cmp(&b.name));
+    methods
+}
+
+/// An OpenRPC document built once per process, e.g. to answer every `rpc.discover`
+/// call with the same document.
+///
+/// Declare it as a `static`, and call [`CachedDocument::get_or_build`] wherever the
+/// document is needed; the schemas are only generated by the first call.
+pub struct CachedDocument(OnceLock<OpenRpc>);
+
+impl CachedDocument {
+    /// Creates an empty cache.
+    pub const fn new() -> Self {
+        Self(OnceLock::new())
+    }
+
+    /// Returns the cached document, building it with [`OpenRpc::build`] if this is
+    /// the first call.
+    ///
+    /// `info` and `methods` are only used by the first call.
+    pub fn get_or_build<'a>(
+        &self,
+        info: impl FnOnce() -> Info,
+        methods: impl IntoIterator<Item = (&'a &'static str, &'a RpcMethod)>,
+    ) -> &OpenRpc {
+        self.0.get_or_init(|| OpenRpc::build(info(), methods))
+    }
+}
+
+impl Default for CachedDocument {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 /// An OpenRPC document.
+///
+/// Documents can be deserialized, e.g. to compare a committed document with a freshly
+/// generated one. Parameters and results shared through
+/// `components.contentDescriptors` are resolved, and stay shared when the document is
+/// serialized again.
 #[derive(Clone, Debug, Serialize, Documented)]
 pub struct OpenRpc {
     /// The OpenRPC specification version.
-    pub openrpc: &'static str,
+    pub openrpc: Cow<'static, str>,
     /// Information about the API.
     pub info: Info,
+    /// The servers the API is available at.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub servers: Vec<Server>,
     /// The available JSON-RPC methods.
     pub methods: Vec<Method>,
     /// The components (schemas) used in the document.
     pub components: Components,
+    /// Additional documentation of the API, e.g. a user guide.
+    #[serde(rename = "externalDocs", skip_serializing_if = "Option::is_none")]
+    pub external_docs: Option<ExternalDocs>,
+}
+
+impl OpenRpc {
+    /// Assembles a document from a generated `METHODS` map (or any other collection of
+    /// named [`RpcMethod`]s), using a single [`Generator`].
+    ///
+    /// Methods that aren't available in this build are left out, and the rest are
+    /// sorted by name, so the output is reproducible. Servers and external docs can
+    /// be added to the returned document afterwards.
+    pub fn build<'a>(
+        info: Info,
+        methods: impl IntoIterator<Item = (&'a &'static str, &'a RpcMethod)>,
+    ) -> Self {
+        Self::build_with(Generator::new(), info, methods)
+    }
+
+    /// Assembles a document as [`OpenRpc::build`] does, using `generator`, e.g. one
+    /// configured with [`Generator::builder`] to add the response envelope.
+    pub fn build_with<'a>(
+        mut generator: Generator,
+        info: Info,
+        methods: impl IntoIterator<Item = (&'a &'static str, &'a RpcMethod)>,
+    ) -> Self {
+        let methods = generate_methods(&mut generator, methods);
+
+        OpenRpc {
+            openrpc: OPENRPC_VERSION.into(),
+            info,
+            servers: vec![],
+            methods,
+            components: generator.into_components(),
+            external_docs: None,
+        }
+    }
+
+    /// Converts the document into a JSON value whose serialization is stable across
+    /// `schemars` and `serde_json` versions.
+    ///
+    /// Object keys within every schema are sorted recursively (regardless of
+    /// `schemars`' keyword ordering or `serde_json`'s `preserve_order` feature), and
+    /// floating-point numbers with an exact integer value are rewritten as integers.
+    /// Use this instead of serializing the document directly when the output is
+    /// committed or compared byte-for-byte.
+    pub fn to_canonical_value(&self) -> serde_json::Result<JsonValue> {
+        let mut document = serde_json::to_value(self)?;
+
+        if let Some(methods) = document
+            .get_mut("methods")
+            .and_then(JsonValue::as_array_mut)
+        {
+            for method in methods {
+                if let Some(params) = method.get_mut("params").and_then(JsonValue::as_array_mut) {
+                    for param in params {
+                        if let Some(schema) = param.get_mut("schema") {
+                            canonicalize(schema);
+                        }
+                    }
+                }
+                if let Some(schema) = method.pointer_mut("/result/schema") {
+                    canonicalize(schema);
+                }
+            }
+        }
+        if let Some(schemas) = document.pointer_mut("/components/schemas") {
+            canonicalize(schemas);
+        }
+        if let Some(descriptors) = document
+            .pointer_mut("/components/contentDescriptors")
+            .and_then(JsonValue::as_object_mut)
+        {
+            for descriptor in descriptors.values_mut() {
+                if let Some(schema) = descriptor.get_mut("schema") {
+                    canonicalize(schema);
+                }
+            }
+        }
+
+        Ok(document)
+    }
+
+    /// Serializes the document as pretty-printed JSON, in its canonical form (see
+    /// [`OpenRpc::to_canonical_value`]), ending with a newline.
+    pub fn to_json_pretty(&self) -> serde_json::Result<String> {
+        let mut json = serde_json::to_string_pretty(&self.to_canonical_value()?)?;
+        json.push('\n');
+        Ok(json)
+    }
+
+    /// Writes the document to `path` as [`OpenRpc::to_json_pretty`] does.
+    ///
+    /// The document is written to a temporary file next to `path` first, then renamed
+    /// over it, so readers (e.g. a server serving the file) never see it half-written.
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let json = self.to_json_pretty()?;
+
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+        let mut temp_name = OsString::from(".");
+        temp_name.push(file_name);
+        temp_name.push(".tmp");
+        let temp_path = path.with_file_name(temp_name);
+
+        fs::write(&temp_path, json)?;
+        fs::rename(&temp_path, path).inspect_err(|_| {
+            let _ = fs::remove_file(&temp_path);
+        })
+    }
+
+    /// Converts the document into a JSON value, with the fields of `overlay` (a JSON
+    /// object, such as the generated `DOCUMENT_OVERLAY`) merged over the top level.
+    ///
+    /// Each overlay field replaces the generated field of the same name entirely.
+    pub fn to_value_with_overlay(&self, overlay: &str) -> serde_json::Result<JsonValue> {
+        let mut document = serde_json::to_value(self)?;
+        let overlay: JsonMap<String, JsonValue> = serde_json::from_str(overlay)?;
+
+        if let Some(document) = document.as_object_mut() {
+            document.extend(overlay);
+        }
+
+        Ok(document)
+    }
+
+    /// Returns a compact summary of the document's methods, in the same order.
+    ///
+    /// This omits all schemas, making it suitable for building search indexes in
+    /// documentation sites.
+    pub fn methods_summary(&self) -> Vec<MethodSummary> {
+        self.methods
+            .iter()
+            .map(|method| MethodSummary {
+                name: method.name.clone(),
+                summary: method.summary.clone(),
+                deprecated: method.deprecated,
+                tags: method.tags.iter().map(|tag| tag.name.clone()).collect(),
+            })
+            .collect()
+    }
+
+    /// Replaces the references to small component schemas with the schemas
+    /// themselves, removing them from the components.
+    ///
+    /// A component schema is inlined when it is referenced exactly once in the whole
+    /// document, by a reference without sibling keywords, doesn't reference other
+    /// component schemas, and consists of at most `max_size` JSON values (e.g. 3 for
+    /// `{"type": "string", "format": "hex"}`). Schemas shared by several methods or
+    /// schemas stay in the components, and the inlined schemas validate exactly what
+    /// their references did.
+    pub fn inline_small_schemas(&mut self, max_size: usize) {
+        // Inlining a schema can leave the schema referencing it without references,
+        // and so inlinable in turn.
+        while self.inline_schemas_once(max_size) {}
+    }
+
+    /// Inlines the small component schemas that don't reference other ones, returning
+    /// whether any was inlined.
+    fn inline_schemas_once(&mut self, max_size: usize) -> bool {
+        let mut uses = BTreeMap::new();
+        for descriptor in self.descriptors() {
+            count_refs(descriptor.schema.as_value(), &mut uses);
+        }
+        for schema in self.components.schemas.values() {
+            count_refs(schema, &mut uses);
+        }
+
+        let inlined: BTreeMap<_, _> = uses
+            .into_iter()
+            .filter(|(_, uses)| *uses == Some(1))
+            .filter_map(|(name, _)| {
+                let schema = self.components.schemas.get(&name)?;
+                let mut refs = BTreeMap::new();
+                count_refs(schema, &mut refs);
+                (refs.is_empty() && json_size(schema) <= max_size).then(|| (name, schema.clone()))
+            })
+            .collect();
+        if inlined.is_empty() {
+            return false;
+        }
+
+        for name in inlined.keys() {
+            self.components.schemas.remove(name);
+        }
+        for method in &mut self.methods {
+            for descriptor in method.params.iter_mut().chain([&mut method.result]) {
+                let mut schema = std::mem::replace(&mut descriptor.schema, true.into()).to_value();
+                inline_refs(&mut schema, &inlined);
+                descriptor.schema = schema
+                    .try_into()
+                    .expect("inlining keeps schemas as objects or booleans");
+            }
+        }
+        for schema in self.components.schemas.values_mut() {
+            inline_refs(schema, &inlined);
+        }
+        true
+    }
+
+    /// Removes the component schemas that no method refers to, directly or through
+    /// other component schemas.
+    ///
+    /// Only references into `components.schemas` are followed, from the parameters and
+    /// results of the methods. Documents whose other parts (e.g. an overlay) refer to
+    /// component schemas are left as they are by not calling this.
+    pub fn prune_unreferenced_schemas(&mut self) {
+        let mut refs = BTreeMap::new();
+        for descriptor in self.descriptors() {
+            count_refs(descriptor.schema.as_value(), &mut refs);
+        }
+
+        let mut reachable = BTreeSet::new();
+        let mut pending: Vec<_> = refs.into_keys().collect();
+        while let Some(name) = pending.pop() {
+            if let Some(schema) = self.components.schemas.get(&name)
+                && reachable.insert(name)
+            {
+                let mut refs = BTreeMap::new();
+                count_refs(schema, &mut refs);
+                pending.extend(refs.into_keys());
+            }
+        }
+        self.components
+            .schemas
+            .retain(|name, _| reachable.contains(name));
+    }
+
+    /// Moves the parameter descriptors shared by several methods into
+    /// `components.contentDescriptors`, replacing each of their occurrences with a
+    /// reference to it.
+    ///
+    /// Descriptors are shared when they are identical, in their name, description,
+    /// schema and every other field. Each is stored under its parameter name, suffixed
+    /// with a number when differing descriptors share a name. Documents for tools that
+    /// don't resolve references to content descriptors are left as they are by not
+    /// calling this.
+    pub fn share_content_descriptors(&mut self) {
+        let params = self
+            .methods
+            .iter_mut()
+            .flat_map(|method| &mut method.params)
+            .collect();
+        share_descriptors(params, &mut self.components.content_descriptors, |param| {
+            Some(param.name.clone())
+        });
+    }
+
+    /// Moves the result descriptors of several methods returning the same component
+    /// schema into `components.contentDescriptors`, replacing each of them with a
+    /// reference to it.
+    ///
+    /// Results are shared when their schema is a reference to a component schema and
+    /// they are identical but for their name, as each is named after its method. A
+    /// shared result is named after the schema instead, e.g. `GetInfo` rather than
+    /// `getinfo_result`, and stored under that name, suffixed with a number when it is
+    /// taken. Results are inline unless this is called.
+    pub fn share_result_descriptors(&mut self) {
+        let results = self
+            .methods
+            .iter_mut()
+            .map(|method| &mut method.result)
+            .collect();
+        share_descriptors(
+            results,
+            &mut self.components.content_descriptors,
+            |result| {
+                let reference = result.schema.get("$ref")?.as_str()?;
+                Some(component_ref_name(reference)?.into())
+            },
+        );
+    }
+
+    /// Returns the descriptors of every method's parameters and result.
+    fn descriptors(&self) -> impl Iterator<Item = &ContentDescriptor> {
+        self.methods
+            .iter()
+            .flat_map(|method| method.params.iter().chain([&method.result]))
+    }
+
+    /// Returns the JSON Pointer of each method and component schema within the
+    /// serialized document, e.g. for deep-linking into a rendered document.
+    ///
+    /// Method pointers follow the order of [`OpenRpc::methods`], so build the index
+    /// once the methods are in their final order.
+    pub fn pointer_index(&self) -> PointerIndex {
+        PointerIndex {
+            methods: self
+                .methods
+                .iter()
+                .enumerate()
+                .map(|(i, method)| (method.name.clone(), format!("/methods/{i}")))
+                .collect(),
+            schemas: self
+                .components
+                .schemas
+                .keys()
+                .map(|name| {
+                    let token = name.replace('~', "~0").replace('/', "~1");
+                    (name.clone(), format!("/components/schemas/{token}"))
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for OpenRpc {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// An [`OpenRpc`] as serialized, before its shared parameters are resolved.
+        #[derive(Deserialize)]
+        struct Document {
+            openrpc: Cow<'static, str>,
+            info: Info,
+            #[serde(default)]
+            servers: Vec<Server>,
+            methods: Vec<Method>,
+            #[serde(default)]
+            components: Components,
+            #[serde(rename = "externalDocs", default)]
+            external_docs: Option<ExternalDocs>,
+        }
+
+        let Document {
+            openrpc,
+            info,
+            servers,
+            mut methods,
+            components,
+            external_docs,
+        } = Document::deserialize(deserializer)?;
+
+        // Shared parameters and results are read as placeholders naming their
+        // descriptor.
+        for param in methods
+            .iter_mut()
+            .flat_map(|method| method.params.iter_mut().chain([&mut method.result]))
+        {
+            if let Some(name) = param.shared_as.take() {
+                let shared = components.content_descriptors.get(&name).ok_or_else(|| {
+                    de::Error::custom(format!("unknown content descriptor `{name}`"))
+                })?;
+                *param = ContentDescriptor {
+                    shared_as: Some(name),
+                    ..shared.clone()
+                };
+            }
+        }
+
+        Ok(OpenRpc {
+            openrpc,
+            info,
+            servers,
+            methods,
+            components,
+            external_docs,
+        })
+    }
 }
 
 impl JsonSchema for OpenRpc {
@@ -129,47 +904,846 @@ impl JsonSchema for OpenRpc {
 }
 
 /// Information about the API.
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Info {
     /// The title of the API.
-    pub title: &'static str,
+    pub title: Cow<'static, str>,
     /// A description of the API.
-    pub description: &'static str,
+    #[serde(default)]
+    pub description: Cow<'static, str>,
+    /// A URL to the terms of service for the API.
+    #[serde(rename = "termsOfService", skip_serializing_if = "Option::is_none")]
+    pub terms_of_service: Option<Cow<'static, str>>,
+    /// Contact information for the API.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact: Option<Contact>,
+    /// License information for the API.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<License>,
     /// The version of the API.
-    pub version: &'static str,
+    pub version: Cow<'static, str>,
+}
+
+impl Info {
+    /// Returns a builder for the API information.
+    ///
+    /// All fields start out empty; use [`info_builder!`](crate::info_builder) to
+    /// default them from the calling crate's Cargo metadata instead.
+    pub fn builder() -> InfoBuilder {
+        InfoBuilder::default()
+    }
+}
+
+/// A builder for [`Info`].
+#[derive(Clone, Debug, Default)]
+pub struct InfoBuilder {
+    info: Info,
+}
+
+impl InfoBuilder {
+    /// Sets the title of the API.
+    pub fn title(mut self, title: impl Into<Cow<'static, str>>) -> Self {
+        self.info.title = title.into();
+        self
+    }
+
+    /// Sets the description of the API.
+    pub fn description(mut self, description: impl Into<Cow<'static, str>>) -> Self {
+        self.info.description = description.into();
+        self
+    }
+
+    /// Sets the version of the API.
+    pub fn version(mut self, version: impl Into<Cow<'static, str>>) -> Self {
+        self.info.version = version.into();
+        self
+    }
+
+    /// Sets the URL to the terms of service for the API.
+    pub fn terms_of_service(mut self, terms_of_service: impl Into<Cow<'static, str>>) -> Self {
+        self.info.terms_of_service = Some(terms_of_service.into());
+        self
+    }
+
+    /// Sets the contact information for the API.
+    pub fn contact(mut self, contact: Contact) -> Self {
+        self.info.contact = Some(contact);
+        self
+    }
+
+    /// Sets the license information for the API.
+    pub fn license(mut self, license: License) -> Self {
+        self.info.license = Some(license);
+        self
+    }
+
+    /// Produces the API information.
+    pub fn build(self) -> Info {
+        self.info
+    }
+}
+
+/// Returns an [`InfoBuilder`] whose title, description, and version default to the
+/// calling crate's `CARGO_PKG_NAME`, `CARGO_PKG_DESCRIPTION`, and `CARGO_PKG_VERSION`.
+///
+/// Each default can still be overridden through the builder.
+#[macro_export]
+macro_rules! info_builder {
+    () => {
+        $crate::openrpc::Info::builder()
+            .title(env!("CARGO_PKG_NAME"))
+            .description(env!("CARGO_PKG_DESCRIPTION"))
+            .version(env!("CARGO_PKG_VERSION"))
+    };
+}
+
+/// Contact information for the API.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Contact {
+    /// The name of the contact person or organization.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<Cow<'static, str>>,
+    /// A URL pointing to the contact information.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<Cow<'static, str>>,
+    /// The email address of the contact person or organization.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<Cow<'static, str>>,
+}
+
+/// License information for the API.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct License {
+    /// The name of the license.
+    pub name: Cow<'static, str>,
+    /// A URL to the license.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<Cow<'static, str>>,
+}
+
+/// A reference to documentation hosted elsewhere.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExternalDocs {
+    /// A description of the documentation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Cow<'static, str>>,
+    /// The URL of the documentation.
+    pub url: Cow<'static, str>,
+}
+
+/// A server the API is available at.
+///
+/// Servers can be deserialized, e.g. to carry them over from a stored document.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Server {
+    /// The name of the server.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<Cow<'static, str>>,
+    /// The URL of the server, which may contain `{variable}` placeholders.
+    pub url: Cow<'static, str>,
+    /// A short summary of the server.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<Cow<'static, str>>,
+    /// A description of the server.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Cow<'static, str>>,
+    /// The variables substituted into the URL's placeholders, by name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<Cow<'static, str>, ServerVariable>,
+}
+
+/// A variable substituted into a [`Server`] URL.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ServerVariable {
+    /// The value used when the client doesn't substitute another one.
+    pub default: Cow<'static, str>,
+    /// The allowed values, if restricted.
+    #[serde(rename = "enum", default, skip_serializing_if = "Vec::is_empty")]
+    pub enum_values: Vec<Cow<'static, str>>,
+    /// A description of the variable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Cow<'static, str>>,
 }
 
 /// A JSON-RPC method.
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug)]
 pub struct Method {
-    name: &'static str,
-    summary: &'static str,
-    description: &'static str,
+    name: Cow<'static, str>,
+    internal_name: Option<Cow<'static, str>>,
+    aliases: Vec<Cow<'static, str>>,
+    summary: Cow<'static, str>,
+    description: Cow<'static, str>,
+    tags: Vec<Tag>,
     params: Vec<ContentDescriptor>,
     result: ContentDescriptor,
-    #[serde(skip_serializing_if = "is_false")]
     deprecated: bool,
+    replaced_by: Option<Cow<'static, str>>,
+    deprecation_note: Option<Cow<'static, str>>,
+    subscription: bool,
+    unsubscribe: Option<Cow<'static, str>>,
+    cost: Option<Cost>,
+    examples: Vec<ExamplePairing>,
+    errors: Vec<Reference>,
+    param_structure: Option<ParamStructure>,
+    overlay: JsonMap<String, JsonValue>,
 }
 
-/// A descriptor for a JSON-RPC method's parameter or result.
+impl Serialize for Method {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        // Fields present in the overlay are replaced by it.
+        macro_rules! field {
+            ($key:literal, $value:expr) => {
+                if !self.overlay.contains_key($key) {
+                    map.serialize_entry($key, $value)?;
+                }
+            };
+        }
+
+        field!("name", &self.name);
+        if let Some(internal_name) = &self.internal_name {
+            field!("x-internal-name", internal_name);
+        }
+        if !self.aliases.is_empty() {
+            field!("x-aliases", &self.aliases);
+        }
+        field!("summary", &self.summary);
+        field!("description", &self.description);
+        if !self.tags.is_empty() {
+            let tags: Vec<_> = self
+                .tags
+                .iter()
+                .map(|tag| Reference {
+                    reference: format!("{TAG_REF_PREFIX}{}", tag.name),
+                })
+                .collect();
+            field!("tags", &tags);
+        }
+        let params: Vec<_> = self
+            .params
+            .iter()
+            .map(|param| match &param.shared_as {
+                Some(name) => Param::Reference(content_descriptor_ref(name)),
+                None => Param::Descriptor(param),
+            })
+            .collect();
+        field!("params", &params);
+        match &self.result.shared_as {
+            Some(name) => field!("result", &content_descriptor_ref(name)),
+            None => field!("result", &self.result),
+        }
+        if let Some(param_structure) = &self.param_structure {
+            field!("paramStructure", param_structure);
+        }
+        if self.deprecated {
+            field!("deprecated", &self.deprecated);
+        }
+        if let Some(replaced_by) = &self.replaced_by {
+            field!("x-replaced-by", replaced_by);
+        }
+        if let Some(deprecation_note) = &self.deprecation_note {
+            field!("x-deprecation-note", deprecation_note);
+        }
+        if self.subscription {
+            field!("x-subscription", &self.subscription);
+        }
+        if let Some(unsubscribe) = &self.unsubscribe {
+            field!("x-unsubscribe", unsubscribe);
+        }
+        if let Some(cost) = &self.cost {
+            field!("x-cost", cost);
+        }
+        if !self.examples.is_empty() {
+            field!("examples", &self.examples);
+        }
+        if !self.errors.is_empty() {
+            field!("errors", &self.errors);
+        }
+        for (key, value) in &self.overlay {
+            map.serialize_entry(key, value)?;
+        }
+
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Method {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// The fields of a [`Method`] as serialized.
+        #[derive(Deserialize)]
+        struct Fields {
+            name: Cow<'static, str>,
+            #[serde(rename = "x-internal-name")]
+            internal_name: Option<Cow<'static, str>>,
+            #[serde(rename = "x-aliases", default)]
+            aliases: Vec<Cow<'static, str>>,
+            #[serde(default)]
+            summary: Cow<'static, str>,
+            #[serde(default)]
+            description: Cow<'static, str>,
+            #[serde(default)]
+            tags: Vec<OwnedTag>,
+            params: Vec<OwnedParam>,
+            result: OwnedParam,
+            #[serde(rename = "paramStructure")]
+            param_structure: Option<ParamStructure>,
+            #[serde(default)]
+            deprecated: bool,
+            #[serde(rename = "x-replaced-by")]
+            replaced_by: Option<Cow<'static, str>>,
+            #[serde(rename = "x-deprecation-note")]
+            deprecation_note: Option<Cow<'static, str>>,
+            #[serde(rename = "x-subscription", default)]
+            subscription: bool,
+            #[serde(rename = "x-unsubscribe")]
+            unsubscribe: Option<Cow<'static, str>>,
+            #[serde(rename = "x-cost")]
+            cost: Option<Cost>,
+            #[serde(default)]
+            examples: Vec<ExamplePairing>,
+            #[serde(default)]
+            errors: Vec<Reference>,
+            /// Fields the generator doesn't produce, e.g. from an overlay.
+            #[serde(flatten)]
+            overlay: JsonMap<String, JsonValue>,
+        }
+
+        /// A method's parameter as serialized, see [`Param`].
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum OwnedParam {
+            Descriptor(ContentDescriptor),
+            Reference(Reference),
+        }
+
+        /// A method's tag as serialized: a reference to the components, or the tag
+        /// itself, as in documents that don't collect their tags.
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum OwnedTag {
+            Tag(Tag),
+            Reference(Reference),
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+        let tags = fields
+            .tags
+            .into_iter()
+            .map(|tag| match tag {
+                OwnedTag::Tag(tag) => Ok(tag),
+                OwnedTag::Reference(Reference { reference }) => {
+                    let name = reference.strip_prefix(TAG_REF_PREFIX).ok_or_else(|| {
+                        de::Error::custom(format!("unsupported tag reference `{reference}`"))
+                    })?;
+                    Ok(Tag {
+                        name: name.to_owned().into(),
+                    })
+                }
+            })
+            .collect::<Result<_, D::Error>>()?;
+        let descriptor = |param, kind| match param {
+            OwnedParam::Descriptor(descriptor) => Ok(descriptor),
+            // Resolved by the document, which holds the shared descriptors.
+            OwnedParam::Reference(Reference { reference }) => {
+                let name =
+                    ref_name(&reference, CONTENT_DESCRIPTOR_REF_PREFIX).ok_or_else(|| {
+                        de::Error::custom(format!("unsupported {kind} reference `{reference}`"))
+                    })?;
+                let mut placeholder = ContentDescriptor::new("", "", false, true.into());
+                placeholder.shared_as = Some(name);
+                Ok(placeholder)
+            }
+        };
+        let params = fields
+            .params
+            .into_iter()
+            .map(|param| descriptor(param, "parameter"))
+            .collect::<Result<_, D::Error>>()?;
+        let result = descriptor(fields.result, "result")?;
+
+        Ok(Method {
+            name: fields.name,
+            internal_name: fields.internal_name,
+            aliases: fields.aliases,
+            summary: fields.summary,
+            description: fields.description,
+            tags,
+            params,
+            result,
+            deprecated: fields.deprecated,
+            replaced_by: fields.replaced_by,
+            deprecation_note: fields.deprecation_note,
+            subscription: fields.subscription,
+            unsubscribe: fields.unsubscribe,
+            cost: fields.cost,
+            examples: fields.examples,
+            errors: fields.errors,
+            param_structure: fields.param_structure,
+            overlay: fields.overlay,
+        })
+    }
+}
+
+impl Method {
+    /// Returns whether `name` is the method's name, its internal name, or one of its
+    /// aliases.
+    pub fn matches_name(&self, name: &str) -> bool {
+        self.name == name
+            || self.internal_name.as_deref() == Some(name)
+            || self.aliases.iter().any(|alias| alias == name)
+    }
+}
+
+/// A compact summary of a JSON-RPC method, produced by [`OpenRpc::methods_summary`].
+#[derive(Clone, Debug, Serialize)]
+pub struct MethodSummary {
+    /// The name of the method.
+    pub name: Cow<'static, str>,
+    /// A short summary of the method.
+    pub summary: Cow<'static, str>,
+    /// Whether the method is deprecated.
+    pub deprecated: bool,
+    /// The names of the tags the method is grouped under.
+    pub tags: Vec<Cow<'static, str>>,
+}
+
+/// The JSON Pointers of a document's methods and schemas, produced by
+/// [`OpenRpc::pointer_index`].
 #[derive(Clone, Debug, Serialize)]
+pub struct PointerIndex {
+    /// The pointer of each method, by method name.
+    pub methods: BTreeMap<Cow<'static, str>, String>,
+    /// The pointer of each component schema, by schema name.
+    pub schemas: BTreeMap<String, String>,
+}
+
+/// Hints about the cost of calling a JSON-RPC method, e.g. for client-side budgeting.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cost {
+    /// The relative weight of a call, e.g. for rate limiting.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight: Option<u64>,
+    /// How long a call may take before the server gives up, in milliseconds.
+    #[serde(rename = "timeoutMs", skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+/// How a JSON-RPC method accepts its parameters, documented as its `paramStructure`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ParamStructure {
+    /// As an object, keyed by parameter name.
+    ByName,
+    /// As an array, in declaration order.
+    ByPosition,
+    /// Either way, as `jsonrpsee` methods do unless restricted.
+    #[default]
+    Either,
+}
+
+/// An error a JSON-RPC method is documented to return.
+#[derive(Clone, Copy, Debug)]
+pub struct MethodError {
+    /// The name of the error among the document's error components.
+    pub name: &'static str,
+    /// The JSON-RPC error code.
+    pub code: i64,
+    /// A short description of the error.
+    pub message: &'static str,
+    /// Additional information about the error, as JSON, if any.
+    pub data: Option<&'static str>,
+}
+
+/// An example call of a JSON-RPC method, pairing parameters with a result.
+#[derive(Clone, Copy, Debug)]
+pub struct MethodExample {
+    /// The name of the example.
+    pub name: &'static str,
+    /// The example's parameters, as `(parameter name, JSON value)` pairs.
+    pub params: &'static [(&'static str, &'static str)],
+    /// The example's result, as JSON.
+    pub result: &'static str,
+}
+
+impl MethodExample {
+    /// Returns the example as an OpenRPC example pairing.
+    fn pairing(&self) -> ExamplePairing {
+        let value = |json| {
+            serde_json::from_str(json).expect("examples are checked to be JSON when generated")
+        };
+        ExamplePairing {
+            name: self.name.into(),
+            params: self
+                .params
+                .iter()
+                .map(|&(name, json)| Example {
+                    name: name.into(),
+                    value: value(json),
+                })
+                .collect(),
+            result: Example {
+                name: self.name.into(),
+                value: value(self.result),
+            },
+        }
+    }
+}
+
+/// A reference to a component of the document.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Reference {
+    #[serde(rename = "$ref")]
+    reference: String,
+}
+
+/// A method's parameter as serialized: its descriptor, or a reference to it once it is
+/// shared through the components.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Param<'a> {
+    Descriptor(&'a ContentDescriptor),
+    Reference(Reference),
+}
+
+/// An example of a JSON-RPC method call.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExamplePairing {
+    name: Cow<'static, str>,
+    #[serde(default)]
+    params: Vec<Example>,
+    result: Example,
+}
+
+/// An example value.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Example {
+    name: Cow<'static, str>,
+    value: JsonValue,
+}
+
+/// A tag used to group JSON-RPC methods.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Tag {
+    name: Cow<'static, str>,
+}
+
+/// A descriptor for a JSON-RPC method's parameter or result.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ContentDescriptor {
-    name: &'static str,
-    summary: &'static str,
-    description: &'static str,
-    #[serde(skip_serializing_if = "is_false")]
+    name: Cow<'static, str>,
+    #[serde(default)]
+    summary: Cow<'static, str>,
+    #[serde(default)]
+    description: Cow<'static, str>,
+    #[serde(default, skip_serializing_if = "is_false")]
     required: bool,
     schema: Schema,
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     deprecated: bool,
+    #[serde(
+        rename = "x-deprecation-note",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    deprecation_note: Option<Cow<'static, str>>,
+    /// The name of the shared descriptor in the components this one is serialized as a
+    /// reference to, if any.
+    #[serde(skip)]
+    shared_as: Option<String>,
 }
 
-/// The components (schemas) used in the OpenRPC document.
-#[derive(Clone, Debug, Serialize)]
+impl ContentDescriptor {
+    /// Constructs a descriptor from an existing schema.
+    ///
+    /// The summary is the first line of `description`, as for descriptors produced
+    /// by a [`Generator`].
+    pub fn new(
+        name: &'static str,
+        description: impl Into<Cow<'static, str>>,
+        required: bool,
+        schema: Schema,
+    ) -> Self {
+        let description = description.into();
+        let summary = match &description {
+            Cow::Borrowed(description) => Cow::Borrowed(first_line(description)),
+            Cow::Owned(description) => Cow::Owned(first_line(description).to_owned()),
+        };
+
+        ContentDescriptor {
+            name: name.into(),
+            summary,
+            description,
+            required,
+            schema,
+            deprecated: false,
+            deprecation_note: None,
+            shared_as: None,
+        }
+    }
+
+    /// Allows the descriptor's schema to also match `null`.
+    pub fn nullable(mut self) -> Self {
+        let nullable_type = match self.schema.get("type") {
+            _ if self.schema.get("enum").is_some() || self.schema.get("const").is_some() => None,
+            Some(JsonValue::String(ty)) => Some(serde_json::json!([ty, "null"])),
+            Some(JsonValue::Array(types)) => {
+                let mut types = types.clone();
+                if !types.iter().any(|ty| ty == "null") {
+                    types.push("null".into());
+                }
+                Some(JsonValue::Array(types))
+            }
+            _ => None,
+        };
+        match nullable_type {
+            Some(ty) => {
+                self.schema.insert("type".into(), ty);
+            }
+            None => {
+                self.schema = schemars::json_schema!({
+                    "anyOf": [self.schema, { "type": "null" }],
+                });
+            }
+        }
+        self
+    }
+
+    /// Sets the `format` keyword of the descriptor's schema.
+    pub fn with_format(mut self, format: &'static str) -> Self {
+        self.schema.insert("format".into(), format.into());
+        self
+    }
+
+    /// Marks the descriptor as deprecated, with the reason given in `note`, if any.
+    ///
+    /// The note is serialized as an `x-deprecation-note` extension, as for methods.
+    pub fn deprecated(mut self, note: Option<&'static str>) -> Self {
+        self.deprecated = true;
+        self.deprecation_note = note.map(Cow::Borrowed);
+        self
+    }
+}
+
+/// The components (schemas and errors) used in the OpenRPC document.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Components {
-    schemas: serde_json::Map<String, JsonValue>,
+    // Sorted by name, so that documents serialize the same way every time.
+    #[serde(default)]
+    schemas: BTreeMap<String, JsonValue>,
+    #[serde(
+        rename = "contentDescriptors",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    content_descriptors: BTreeMap<String, ContentDescriptor>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    tags: BTreeMap<String, Tag>,
+    #[serde(default, skip_serializing_if = "JsonMap::is_empty")]
+    errors: JsonMap<String, JsonValue>,
+}
+
+fn first_line(s: &str) -> &str {
+    s.split_once('\n').map(|(line, _)| line).unwrap_or(s)
 }
 
 fn is_false(b: &bool) -> bool {
     !b
 }
+
+/// Moves the `descriptors` that are identical to others into `components`, replacing
+/// each of their occurrences with a reference to it.
+///
+/// Only descriptors `shared_name` names are shared, under that name, and compared as if
+/// they had it. Each is stored under its name, suffixed with a number when differing
+/// descriptors share a name.
+fn share_descriptors(
+    mut descriptors: Vec<&mut ContentDescriptor>,
+    components: &mut BTreeMap<String, ContentDescriptor>,
+    shared_name: impl Fn(&ContentDescriptor) -> Option<Cow<'static, str>>,
+) {
+    // Identical descriptors serialize identically.
+    let key = |descriptor: &ContentDescriptor| {
+        let name = shared_name(descriptor)?;
+        let renamed = ContentDescriptor {
+            name,
+            ..descriptor.clone()
+        };
+        Some(serde_json::to_string(&renamed).expect("descriptors serialize to JSON"))
+    };
+
+    // Count them in order of first occurrence, for stable component names.
+    let mut uses: Vec<(String, usize)> = vec![];
+    for descriptor in descriptors.iter().filter(|d| d.shared_as.is_none()) {
+        let Some(key) = key(descriptor) else {
+            continue;
+        };
+        match uses.iter_mut().find(|(other, _)| *other == key) {
+            Some((_, count)) => *count += 1,
+            None => uses.push((key, 1)),
+        }
+    }
+
+    for (key_of_shared, _) in uses.into_iter().filter(|(_, count)| *count > 1) {
+        let mut shared_as = None;
+        for descriptor in descriptors.iter_mut() {
+            if descriptor.shared_as.is_some() || key(descriptor).as_ref() != Some(&key_of_shared) {
+                continue;
+            }
+            if let Some(name) = shared_name(descriptor) {
+                descriptor.name = name;
+            }
+            let name = shared_as.get_or_insert_with(|| {
+                let name = (1..)
+                    .map(|n| match n {
+                        1 => descriptor.name.to_string(),
+                        n => format!("{}_{n}", descriptor.name),
+                    })
+                    .find(|name| !components.contains_key(name))
+                    .expect("some numbered name is free");
+                components.insert(name.clone(), (**descriptor).clone());
+                name
+            });
+            descriptor.shared_as = Some(name.clone());
+        }
+    }
+}
+
+/// The prefix of references to component schemas.
+const COMPONENT_REF_PREFIX: &str = "#/components/schemas/";
+
+/// The prefix of references to component tags.
+const TAG_REF_PREFIX: &str = "#/components/tags/";
+
+/// The prefix of references to component content descriptors.
+const CONTENT_DESCRIPTOR_REF_PREFIX: &str = "#/components/contentDescriptors/";
+
+/// Returns the name of the component schema `reference` points to, if any.
+fn component_ref_name(reference: &str) -> Option<String> {
+    ref_name(reference, COMPONENT_REF_PREFIX)
+}
+
+/// Returns a reference to the component content descriptor `name`.
+fn content_descriptor_ref(name: &str) -> Reference {
+    // Apply the JSON Pointer escapes, then percent-encode what a URI fragment can't hold.
+    let token = name.replace('~', "~0").replace('/', "~1");
+    let mut reference = CONTENT_DESCRIPTOR_REF_PREFIX.to_string();
+    for byte in token.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~!$&'()*+,;=:@/?".contains(&byte) {
+            reference.push(byte.into());
+        } else {
+            reference.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    Reference { reference }
+}
+
+/// Returns the name of the component `reference` points to, if it starts with
+/// `prefix`.
+fn ref_name(reference: &str, prefix: &str) -> Option<String> {
+    let token = reference.strip_prefix(prefix)?;
+
+    // Undo the percent-encoding of the URI fragment, then the JSON Pointer escapes.
+    let mut bytes = vec![];
+    let mut rest = token.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        match tail
+            .get(..2)
+            .and_then(|hex| u8::from_str_radix(str::from_utf8(hex).ok()?, 16).ok())
+        {
+            Some(decoded) if byte == b'%' => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    Some(
+        String::from_utf8(bytes)
+            .ok()?
+            .replace("~1", "/")
+            .replace("~0", "~"),
+    )
+}
+
+/// Counts the references to each component schema within `value`. The count is
+/// `None` for schemas also referenced with sibling keywords, which aren't inlined.
+fn count_refs(value: &JsonValue, uses: &mut BTreeMap<String, Option<usize>>) {
+    match value {
+        JsonValue::Object(map) => {
+            if let Some(name) = map
+                .get("$ref")
+                .and_then(JsonValue::as_str)
+                .and_then(component_ref_name)
+            {
+                let count = uses.entry(name).or_insert(Some(0));
+                *count = count.filter(|_| map.len() == 1).map(|count| count + 1);
+            }
+            map.values().for_each(|value| count_refs(value, uses));
+        }
+        JsonValue::Array(items) => items.iter().for_each(|value| count_refs(value, uses)),
+        _ => {}
+    }
+}
+
+/// Replaces the references to the `inlined` component schemas within `value`.
+fn inline_refs(value: &mut JsonValue, inlined: &BTreeMap<String, JsonValue>) {
+    match value {
+        JsonValue::Object(map) => {
+            if map.len() == 1
+                && let Some(schema) = map
+                    .get("$ref")
+                    .and_then(JsonValue::as_str)
+                    .and_then(component_ref_name)
+                    .and_then(|name| inlined.get(&name))
+            {
+                *value = schema.clone();
+                return;
+            }
+            map.values_mut()
+                .for_each(|value| inline_refs(value, inlined));
+        }
+        JsonValue::Array(items) => items
+            .iter_mut()
+            .for_each(|value| inline_refs(value, inlined)),
+        _ => {}
+    }
+}
+
+/// Returns the number of JSON values in `value`, including itself.
+fn json_size(value: &JsonValue) -> usize {
+    1 + match value {
+        JsonValue::Object(map) => map.values().map(json_size).sum(),
+        JsonValue::Array(items) => items.iter().map(json_size).sum(),
+        _ => 0,
+    }
+}
+
+fn canonicalize(value: &mut JsonValue) {
+    /// The largest magnitude below which every integer is exactly representable.
+    const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+    match value {
+        JsonValue::Object(map) => {
+            let mut entries: Vec<_> = std::mem::take(map).into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            for (key, mut value) in entries {
+                canonicalize(&mut value);
+                map.insert(key, value);
+            }
+        }
+        JsonValue::Array(items) => items.iter_mut().for_each(canonicalize),
+        JsonValue::Number(number) => {
+            if let Some(float) = number.as_f64()
+                && number.is_f64()
+                && float.fract() == 0.0
+                && float.abs() <= MAX_SAFE_INTEGER
+            {
+                *number = (float as i64).into();
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,494 @@
+//! Generation of client code from OpenRPC documents.
+
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+use super::{ContentDescriptor, Method, OpenRpc, ParamStructure, component_ref_name};
+
+/// Words TypeScript doesn't accept as parameter names.
+const RESERVED_WORDS: &[&str] = &[
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+/// Generates a TypeScript module for the API `doc` describes.
+///
+/// The module declares a type for each schema in `components.schemas`, and a `Client`
+/// class with a typed method per JSON-RPC method, sending its requests through a
+/// `Transport` the caller provides (e.g. over HTTP). Schemas are mapped structurally:
+/// scalars, arrays, tuples, objects, maps, enums, unions and references to other
+/// component schemas. Keywords without a TypeScript counterpart, such as `format` or
+/// `minimum`, are left out, and schemas that can't be mapped are typed `unknown`.
+/// Subscriptions are left out of the client, as they need a transport carrying
+/// notifications.
+pub fn typescript(doc: &OpenRpc) -> String {
+    let mut contents = format!(
+        "// Generated from the OpenRPC document of {} {}.\n",
+        doc.info.title, doc.info.version
+    );
+
+    for (name, schema) in &doc.components.schemas {
+        contents.push('\n');
+        push_doc(&mut contents, "", description(schema), &[]);
+        match schema {
+            JsonValue::Object(map) if is_interface(map) => {
+                contents.push_str(&format!(
+                    "export interface {} {}\n",
+                    identifier(name),
+                    object_type(map, Some(0))
+                ));
+            }
+            _ => {
+                contents.push_str(&format!(
+                    "export type {} = {};\n",
+                    identifier(name),
+                    ts_type(schema, Some(0))
+                ));
+            }
+        }
+    }
+
+    contents.push_str(
+        "
+/** Sends JSON-RPC requests, resolving to their results. */
+export interface Transport {
+  request(method: string, params: unknown[] | Record<string, unknown>): Promise<unknown>;
+}
+
+/** Drops the trailing parameters that weren't given, leaving them to their defaults. */
+function positional(params: unknown[]): unknown[] {
+  let end = params.length;
+  while (end > 0 && params[end - 1] === undefined) {
+    end--;
+  }
+  return params.slice(0, end);
+}
+",
+    );
+
+    contents.push('\n');
+    push_doc(
+        &mut contents,
+        "",
+        &format!("A client of the {} API.", doc.info.title),
+        &[],
+    );
+    contents.push_str("export class Client {\n");
+    contents.push_str("  constructor(private readonly transport: Transport) {}\n");
+    for method in doc.methods.iter().filter(|method| !method.subscription) {
+        contents.push('\n');
+        push_method(&mut contents, method);
+    }
+    contents.push_str("}\n");
+
+    contents
+}
+
+/// Appends the client method calling `method`.
+fn push_method(contents: &mut String, method: &Method) {
+    let names: Vec<_> = method
+        .params
+        .iter()
+        .map(|param| param_identifier(&param.name))
+        .collect();
+
+    let mut tags: Vec<_> = method
+        .params
+        .iter()
+        .zip(&names)
+        .filter(|(param, _)| !param.description.is_empty() || param.deprecated)
+        .map(|(param, name)| {
+            let mut description = vec![];
+            if param.deprecated {
+                description.push("Deprecated.");
+                description.extend(param.deprecation_note.as_deref());
+            }
+            description.push(param.description.trim());
+            format!("@param {name} {}", description.join(" ").trim_end())
+        })
+        .collect();
+    if method.deprecated {
+        let note = method.deprecation_note.as_deref().unwrap_or_default();
+        tags.push(format!("@deprecated {note}").trim_end().to_string());
+    }
+    push_doc(contents, "  ", &method.description, &tags);
+
+    // Parameters may only be left out when all of those following them can be too.
+    let mut params = vec![];
+    let mut optional = true;
+    for (param, name) in method.params.iter().zip(&names).rev() {
+        optional &= !param.required;
+        let ty = descriptor_type(param);
+        params.push(match (optional, param.required) {
+            (true, _) => format!("{name}?: {ty}"),
+            (false, false) => format!("{name}: {ty} | undefined"),
+            (false, true) => format!("{name}: {ty}"),
+        });
+    }
+    params.reverse();
+
+    let args = match method.param_structure {
+        Some(ParamStructure::ByName) => {
+            let fields: Vec<_> = method
+                .params
+                .iter()
+                .zip(&names)
+                .map(|(param, name)| {
+                    if *name == param.name {
+                        name.clone()
+                    } else {
+                        format!("{}: {name}", property_name(&param.name))
+                    }
+                })
+                .collect();
+            if fields.is_empty() {
+                "{}".to_string()
+            } else {
+                format!("{{ {} }}", fields.join(", "))
+            }
+        }
+        _ if names.is_empty() => "[]".to_string(),
+        _ => format!("positional([{}])", names.join(", ")),
+    };
+
+    let result = descriptor_type(&method.result);
+    contents.push_str(&format!(
+        "  {}({}): Promise<{result}> {{\n",
+        property_name(&method.name),
+        params.join(", ")
+    ));
+    contents.push_str(&format!(
+        "    return this.transport.request({}, {args}) as Promise<{result}>;\n",
+        string_literal(&method.name)
+    ));
+    contents.push_str("  }\n");
+}
+
+/// Appends a doc comment holding `text` and the given tags, indented by `indent`.
+fn push_doc(contents: &mut String, indent: &str, text: &str, tags: &[String]) {
+    let text = text.trim().replace("*/", "*\\/");
+    let tags: Vec<_> = tags.iter().map(|tag| tag.replace("*/", "*\\/")).collect();
+    let mut lines: Vec<_> = text.lines().map(str::trim_end).collect();
+    if !tags.is_empty() {
+        if !lines.is_empty() {
+            lines.push("");
+        }
+        // Tags spanning several lines continue on lines of the comment too.
+        lines.extend(tags.iter().flat_map(|tag| tag.lines().map(str::trim_end)));
+    }
+    match lines.as_slice() {
+        [] => {}
+        [line] => contents.push_str(&format!("{indent}/** {line} */\n")),
+        lines => {
+            contents.push_str(&format!("{indent}/**\n"));
+            for line in lines {
+                if line.is_empty() {
+                    contents.push_str(&format!("{indent} *\n"));
+                } else {
+                    contents.push_str(&format!("{indent} * {line}\n"));
+                }
+            }
+            contents.push_str(&format!("{indent} */\n"));
+        }
+    }
+}
+
+/// Returns the TypeScript type of a descriptor's schema, on a single line.
+fn descriptor_type(descriptor: &ContentDescriptor) -> String {
+    ts_type(descriptor.schema.as_value(), None)
+}
+
+/// Returns the TypeScript type matching `schema`, with any object members on lines of
+/// their own nested one level deeper than `indent`, or on a single line without it.
+fn ts_type(schema: &JsonValue, indent: Option<usize>) -> String {
+    let map = match schema {
+        JsonValue::Bool(true) => return "unknown".into(),
+        JsonValue::Bool(false) => return "never".into(),
+        JsonValue::Object(map) => map,
+        _ => return "unknown".into(),
+    };
+
+    if let Some(reference) = map.get("$ref").and_then(JsonValue::as_str) {
+        return component_ref_name(reference)
+            .map(|name| identifier(&name))
+            .unwrap_or_else(|| "unknown".into());
+    }
+    if let Some(value) = map.get("const") {
+        return literal_type(value);
+    }
+    if let Some(JsonValue::Array(values)) = map.get("enum") {
+        return union(values.iter().map(literal_type));
+    }
+    for keyword in ["anyOf", "oneOf"] {
+        if let Some(JsonValue::Array(schemas)) = map.get(keyword) {
+            return union(schemas.iter().map(|schema| ts_type(schema, indent)));
+        }
+    }
+    if let Some(JsonValue::Array(schemas)) = map.get("allOf") {
+        let types: Vec<_> = schemas
+            .iter()
+            .map(|schema| ts_type(schema, indent))
+            // Unions bind more loosely than intersections.
+            .map(|ty| {
+                if ty.contains(" | ") {
+                    format!("({ty})")
+                } else {
+                    ty
+                }
+            })
+            .collect();
+        return match types.len() {
+            0 => "unknown".into(),
+            1 => types.into_iter().next().unwrap_or_default(),
+            _ => types.join(" & "),
+        };
+    }
+
+    match map.get("type") {
+        Some(JsonValue::String(ty)) => named_type(ty, map, indent),
+        Some(JsonValue::Array(types)) => union(
+            types
+                .iter()
+                .filter_map(JsonValue::as_str)
+                .map(|ty| named_type(ty, map, indent)),
+        ),
+        _ if map.contains_key("properties") => object_type(map, indent),
+        _ if map.contains_key("items") || map.contains_key("prefixItems") => {
+            array_type(map, indent)
+        }
+        _ => "unknown".into(),
+    }
+}
+
+/// Returns the TypeScript type of the JSON Schema type `ty`, described by `map`.
+fn named_type(ty: &str, map: &JsonMap<String, JsonValue>, indent: Option<usize>) -> String {
+    match ty {
+        "string" => "string".into(),
+        "integer" | "number" => "number".into(),
+        "boolean" => "boolean".into(),
+        "null" => "null".into(),
+        "array" => array_type(map, indent),
+        "object" => object_type(map, indent),
+        _ => "unknown".into(),
+    }
+}
+
+/// Returns the TypeScript type of an array schema, as a tuple if its items are listed
+/// by position.
+fn array_type(map: &JsonMap<String, JsonValue>, indent: Option<usize>) -> String {
+    let items = match (map.get("prefixItems"), map.get("items")) {
+        (Some(JsonValue::Array(items)), _) | (None, Some(JsonValue::Array(items))) => {
+            let items: Vec<_> = items.iter().map(|item| ts_type(item, indent)).collect();
+            return format!("[{}]", items.join(", "));
+        }
+        (_, Some(items)) => ts_type(items, indent),
+        _ => "unknown".into(),
+    };
+    if is_simple(&items) {
+        format!("{items}[]")
+    } else {
+        format!("Array<{items}>")
+    }
+}
+
+/// Returns the TypeScript type of an object schema: an object type listing its
+/// properties, or a `Record` for maps.
+fn object_type(map: &JsonMap<String, JsonValue>, indent: Option<usize>) -> String {
+    let additional = map.get("additionalProperties");
+    let properties = match map.get("properties") {
+        Some(JsonValue::Object(properties)) if !properties.is_empty() => properties,
+        _ => {
+            return match additional {
+                Some(JsonValue::Bool(false)) => "Record<string, never>".into(),
+                Some(schema) => format!("Record<string, {}>", ts_type(schema, indent)),
+                None => "Record<string, unknown>".into(),
+            };
+        }
+    };
+    let required: Vec<_> = match map.get("required") {
+        Some(JsonValue::Array(required)) => required.iter().filter_map(JsonValue::as_str).collect(),
+        _ => vec![],
+    };
+
+    let mut members: Vec<_> = properties
+        .iter()
+        .map(|(name, schema)| {
+            let optional = if required.contains(&name.as_str()) {
+                ""
+            } else {
+                "?"
+            };
+            let ty = ts_type(schema, indent.map(|indent| indent + 1));
+            (
+                format!("{}{optional}: {ty}", property_name(name)),
+                description(schema),
+            )
+        })
+        .collect();
+    // Other properties may hold any value, as an index signature must admit the types
+    // of all listed properties.
+    if additional.is_some_and(|additional| *additional != JsonValue::Bool(false)) {
+        members.push(("[key: string]: unknown".into(), ""));
+    }
+
+    let Some(indent) = indent else {
+        let members: Vec<_> = members.into_iter().map(|(member, _)| member).collect();
+        return format!("{{ {} }}", members.join("; "));
+    };
+    let inner = "  ".repeat(indent + 1);
+    let mut contents = "{\n".to_string();
+    for (member, description) in members {
+        push_doc(&mut contents, &inner, description, &[]);
+        contents.push_str(&format!("{inner}{member};\n"));
+    }
+    contents.push_str(&"  ".repeat(indent));
+    contents.push('}');
+    contents
+}
+
+/// Returns whether a component schema is declared as an interface, rather than as a
+/// type alias.
+fn is_interface(map: &JsonMap<String, JsonValue>) -> bool {
+    let is_object = match map.get("type") {
+        Some(JsonValue::String(ty)) => ty == "object",
+        None => true,
+        _ => false,
+    };
+    is_object
+        && ["$ref", "const", "enum", "anyOf", "oneOf", "allOf"]
+            .iter()
+            .all(|keyword| !map.contains_key(*keyword))
+        && matches!(map.get("properties"), Some(JsonValue::Object(properties)) if !properties.is_empty())
+}
+
+/// Returns the union of `types`, leaving out duplicates.
+fn union(types: impl Iterator<Item = String>) -> String {
+    let mut unique: Vec<String> = vec![];
+    for ty in types {
+        if !unique.contains(&ty) {
+            unique.push(ty);
+        }
+    }
+    match unique.len() {
+        0 => "never".into(),
+        _ => unique.join(" | "),
+    }
+}
+
+/// Returns the literal type of a JSON value, if TypeScript has one.
+fn literal_type(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Array(_) | JsonValue::Object(_) => "unknown".into(),
+        value => value.to_string(),
+    }
+}
+
+/// Returns whether `ty` can be followed by `[]` without parentheses.
+fn is_simple(ty: &str) -> bool {
+    ty.chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '[' | ']'))
+}
+
+/// Returns the description of a schema, if any.
+fn description(schema: &JsonValue) -> &str {
+    schema
+        .get("description")
+        .and_then(JsonValue::as_str)
+        .unwrap_or_default()
+}
+
+/// Returns whether `name` is a valid TypeScript identifier.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || matches!(c, '_' | '$'))
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '$'))
+}
+
+/// Returns a TypeScript identifier for the type named `name`.
+fn identifier(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '$' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else {
+        name
+    }
+}
+
+/// Returns a TypeScript identifier for the parameter named `name`.
+fn param_identifier(name: &str) -> String {
+    let name = identifier(name);
+    if RESERVED_WORDS.contains(&name.as_str()) {
+        format!("{name}_")
+    } else {
+        name
+    }
+}
+
+/// Returns `name` as an object property or method name, quoted if it isn't an
+/// identifier.
+fn property_name(name: &str) -> String {
+    if is_identifier(name) {
+        name.to_string()
+    } else {
+        string_literal(name)
+    }
+}
+
+/// Returns `value` as a TypeScript string literal.
+fn string_literal(value: &str) -> String {
+    JsonValue::String(value.to_string()).to_string()
+}
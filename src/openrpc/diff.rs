@@ -0,0 +1,216 @@
+//! Comparison of OpenRPC documents, e.g. to detect breaking changes between releases.
+
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use super::{ContentDescriptor, Method, OpenRpc, canonicalize};
+
+/// The changes from one document to another, produced by [`diff`].
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct DiffReport {
+    /// Every change found, ordered by the methods of the old document, followed by the
+    /// methods added in the new one.
+    pub changes: Vec<Change>,
+}
+
+impl DiffReport {
+    /// Returns whether any change may break existing clients.
+    pub fn is_breaking(&self) -> bool {
+        self.changes.iter().any(|change| change.breaking)
+    }
+
+    /// Returns the changes that may break existing clients.
+    pub fn breaking_changes(&self) -> impl Iterator<Item = &Change> {
+        self.changes.iter().filter(|change| change.breaking)
+    }
+}
+
+impl fmt::Display for DiffReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.changes.is_empty() {
+            return write!(f, "no changes");
+        }
+        for (i, change) in self.changes.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A single change to a method, or to one of its parameters.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Change {
+    /// The name of the method, as documented in the old document (or the new one, for
+    /// added methods).
+    pub method: String,
+    /// The name of the parameter, for parameter changes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub param: Option<String>,
+    /// What changed.
+    pub kind: ChangeKind,
+    /// Whether the change may break existing clients.
+    pub breaking: bool,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = if self.breaking {
+            "breaking"
+        } else {
+            "non-breaking"
+        };
+        let method = &self.method;
+        let param = self.param.as_deref().unwrap_or_default();
+        write!(f, "{severity}: ")?;
+        match self.kind {
+            ChangeKind::MethodRemoved => write!(f, "method `{method}` was removed"),
+            ChangeKind::MethodAdded => write!(f, "method `{method}` was added"),
+            ChangeKind::MethodDeprecated => write!(f, "method `{method}` was deprecated"),
+            ChangeKind::ResultSchemaChanged => {
+                write!(f, "the result schema of method `{method}` changed")
+            }
+            ChangeKind::ParamRemoved => {
+                write!(f, "parameter `{param}` of method `{method}` was removed")
+            }
+            ChangeKind::ParamAdded => {
+                write!(f, "parameter `{param}` was added to method `{method}`")
+            }
+            ChangeKind::ParamBecameRequired => {
+                write!(
+                    f,
+                    "parameter `{param}` of method `{method}` became required"
+                )
+            }
+            ChangeKind::ParamBecameOptional => {
+                write!(
+                    f,
+                    "parameter `{param}` of method `{method}` became optional"
+                )
+            }
+        }
+    }
+}
+
+/// The kind of a [`Change`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChangeKind {
+    /// The method is no longer in the document, under its name or as an alias.
+    MethodRemoved,
+    /// The method is new.
+    MethodAdded,
+    /// The method became deprecated.
+    MethodDeprecated,
+    /// The schema of the method's result (or subscription item) changed.
+    ResultSchemaChanged,
+    /// The parameter is no longer accepted.
+    ParamRemoved,
+    /// The parameter is new; this is breaking if it is required.
+    ParamAdded,
+    /// The parameter was optional, and is now required.
+    ParamBecameRequired,
+    /// The parameter was required, and is now optional.
+    ParamBecameOptional,
+}
+
+/// Compares the methods of two documents, classifying each change as breaking or not.
+///
+/// Methods are matched by name, and a method of `old` is still present if `new`
+/// documents it under an alias. Parameters are matched by name. Result schemas are
+/// compared as written in their descriptors, so changes to the component schemas
+/// they reference aren't reported.
+pub fn diff(old: &OpenRpc, new: &OpenRpc) -> DiffReport {
+    let mut changes = vec![];
+    let method_change = |method: &Method, kind, breaking| Change {
+        method: method.name.to_string(),
+        param: None,
+        kind,
+        breaking,
+    };
+
+    for old_method in &old.methods {
+        let Some(new_method) = new
+            .methods
+            .iter()
+            .find(|method| method.matches_name(&old_method.name))
+        else {
+            changes.push(method_change(old_method, ChangeKind::MethodRemoved, true));
+            continue;
+        };
+
+        if new_method.deprecated && !old_method.deprecated {
+            changes.push(method_change(
+                old_method,
+                ChangeKind::MethodDeprecated,
+                false,
+            ));
+        }
+        if schema_value(&old_method.result) != schema_value(&new_method.result) {
+            changes.push(method_change(
+                old_method,
+                ChangeKind::ResultSchemaChanged,
+                true,
+            ));
+        }
+
+        let param_change = |param: &ContentDescriptor, kind, breaking| Change {
+            method: old_method.name.to_string(),
+            param: Some(param.name.to_string()),
+            kind,
+            breaking,
+        };
+        for old_param in &old_method.params {
+            match new_method
+                .params
+                .iter()
+                .find(|param| param.name == old_param.name)
+            {
+                None => changes.push(param_change(old_param, ChangeKind::ParamRemoved, true)),
+                Some(new_param) if new_param.required && !old_param.required => changes.push(
+                    param_change(old_param, ChangeKind::ParamBecameRequired, true),
+                ),
+                Some(new_param) if !new_param.required && old_param.required => changes.push(
+                    param_change(old_param, ChangeKind::ParamBecameOptional, false),
+                ),
+                Some(_) => {}
+            }
+        }
+        for new_param in &new_method.params {
+            if !old_method
+                .params
+                .iter()
+                .any(|param| param.name == new_param.name)
+            {
+                changes.push(param_change(
+                    new_param,
+                    ChangeKind::ParamAdded,
+                    new_param.required,
+                ));
+            }
+        }
+    }
+
+    for new_method in &new.methods {
+        if !old
+            .methods
+            .iter()
+            .any(|method| new_method.matches_name(&method.name))
+        {
+            changes.push(method_change(new_method, ChangeKind::MethodAdded, false));
+        }
+    }
+
+    DiffReport { changes }
+}
+
+/// Returns the canonical JSON of a descriptor's schema, for comparison.
+fn schema_value(descriptor: &ContentDescriptor) -> JsonValue {
+    let mut schema = descriptor.schema.as_value().clone();
+    canonicalize(&mut schema);
+    schema
+}
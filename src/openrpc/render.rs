@@ -0,0 +1,347 @@
+//! Rendering of OpenRPC documents as human-readable documentation.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+use super::{ContentDescriptor, Method, OpenRpc, component_ref_name};
+
+/// Renders `doc` as a Markdown page.
+///
+/// The page lists the methods, then has a section per method with its description,
+/// a table of its parameters and its result, followed by a section per schema in
+/// `components.schemas`. Types are written out readably, with references to
+/// component schemas linking to their sections, so the page can be embedded in a
+/// documentation site as is. Names whose anchors would collide, e.g. `getInfo` and
+/// `getinfo`, get anchors with a numeric suffix.
+pub fn markdown(doc: &OpenRpc) -> String {
+    let anchors = Anchors::new(doc);
+    let mut contents = format!("# {}\n\n", doc.info.title);
+    contents.push_str(&format!("Version {}\n", doc.info.version));
+    push_paragraph(&mut contents, &doc.info.description);
+
+    contents.push_str("\n## Methods\n\n");
+    for method in &doc.methods {
+        contents.push_str(&format!(
+            "- [`{}`](#{})",
+            method.name,
+            anchors.method(&method.name)
+        ));
+        if !method.summary.is_empty() {
+            contents.push_str(&format!(": {}", method.summary.trim()));
+        }
+        contents.push('\n');
+    }
+    for method in &doc.methods {
+        push_method(&mut contents, &anchors, method);
+    }
+
+    if !doc.components.schemas.is_empty() {
+        contents.push_str("\n## Schemas\n");
+        for (name, schema) in &doc.components.schemas {
+            push_schema(&mut contents, &anchors, name, schema);
+        }
+    }
+
+    contents
+}
+
+/// Appends the section documenting `method`.
+fn push_method(contents: &mut String, anchors: &Anchors, method: &Method) {
+    contents.push_str(&format!(
+        "\n<a id=\"{}\"></a>\n\n### `{}`\n",
+        anchors.method(&method.name),
+        method.name
+    ));
+    if method.deprecated {
+        match method.deprecation_note.as_deref() {
+            Some(note) => contents.push_str(&format!("\n> **Deprecated.** {note}\n")),
+            None => contents.push_str("\n> **Deprecated.**\n"),
+        }
+    }
+    if method.description.trim().is_empty() {
+        push_paragraph(contents, &method.summary);
+    } else {
+        push_paragraph(contents, &method.description);
+    }
+    if !method.aliases.is_empty() {
+        let aliases: Vec<_> = method
+            .aliases
+            .iter()
+            .map(|alias| format!("`{alias}`"))
+            .collect();
+        push_paragraph(
+            contents,
+            &format!("Also callable as {}.", aliases.join(", ")),
+        );
+    }
+
+    if method.params.is_empty() {
+        push_paragraph(contents, "No parameters.");
+    } else {
+        contents.push_str("\n**Parameters**\n\n");
+        contents.push_str("| Name | Type | Required | Description |\n");
+        contents.push_str("| --- | --- | --- | --- |\n");
+        for param in &method.params {
+            contents.push_str(&format!(
+                "| `{}` | {} | {} | {} |\n",
+                param.name,
+                cell(&type_name(anchors, param.schema.as_value())),
+                if param.required { "Yes" } else { "No" },
+                cell(&param_description(param)),
+            ));
+        }
+    }
+
+    let label = if method.subscription {
+        "Notifications"
+    } else {
+        "Result"
+    };
+    push_paragraph(
+        contents,
+        &format!("**{label}**: {}", descriptor_type(anchors, &method.result)),
+    );
+    push_paragraph(contents, &method.result.description);
+}
+
+/// Appends the section documenting the component schema `name`.
+fn push_schema(contents: &mut String, anchors: &Anchors, name: &str, schema: &JsonValue) {
+    contents.push_str(&format!(
+        "\n<a id=\"{}\"></a>\n\n### `{name}`\n",
+        anchors.schema(name)
+    ));
+    push_paragraph(contents, description(schema));
+
+    match schema.get("properties") {
+        Some(JsonValue::Object(properties)) if !properties.is_empty() => {
+            let required = required(schema);
+            contents.push_str("\n| Property | Type | Required | Description |\n");
+            contents.push_str("| --- | --- | --- | --- |\n");
+            for (property, schema) in properties {
+                contents.push_str(&format!(
+                    "| `{property}` | {} | {} | {} |\n",
+                    cell(&type_name(anchors, schema)),
+                    if required.contains(&property.as_str()) {
+                        "Yes"
+                    } else {
+                        "No"
+                    },
+                    cell(description(schema)),
+                ));
+            }
+        }
+        _ => push_paragraph(
+            contents,
+            &format!("**Type**: {}", type_name(anchors, schema)),
+        ),
+    }
+}
+
+/// Appends `text` as a paragraph, if it isn't empty.
+fn push_paragraph(contents: &mut String, text: &str) {
+    let text = text.trim();
+    if !text.is_empty() {
+        contents.push_str(&format!("\n{text}\n"));
+    }
+}
+
+/// Returns the description of a parameter, starting with its deprecation, if any.
+fn param_description(param: &ContentDescriptor) -> String {
+    let mut description = vec![];
+    if param.deprecated {
+        description.push("**Deprecated.**");
+        description.extend(param.deprecation_note.as_deref());
+    }
+    description.push(param.description.trim());
+    description.join(" ")
+}
+
+/// Returns the readable type of a descriptor's schema.
+fn descriptor_type(anchors: &Anchors, descriptor: &ContentDescriptor) -> String {
+    type_name(anchors, descriptor.schema.as_value())
+}
+
+/// Returns a readable name of the type `schema` describes, linking to the sections of
+/// the component schemas it refers to.
+fn type_name(anchors: &Anchors, schema: &JsonValue) -> String {
+    let map = match schema {
+        JsonValue::Bool(false) => return "nothing".into(),
+        JsonValue::Object(map) => map,
+        _ => return "any".into(),
+    };
+
+    if let Some(reference) = map.get("$ref").and_then(JsonValue::as_str) {
+        return match component_ref_name(reference) {
+            Some(name) => format!("[`{name}`](#{})", anchors.schema(&name)),
+            None => format!("`{reference}`"),
+        };
+    }
+    if let Some(value) = map.get("const") {
+        return format!("`{value}`");
+    }
+    if let Some(JsonValue::Array(values)) = map.get("enum") {
+        let values: Vec<_> = values.iter().map(|value| format!("`{value}`")).collect();
+        return format!("one of {}", values.join(", "));
+    }
+    for keyword in ["anyOf", "oneOf"] {
+        if let Some(JsonValue::Array(schemas)) = map.get(keyword) {
+            let types: Vec<_> = schemas
+                .iter()
+                .map(|schema| type_name(anchors, schema))
+                .collect();
+            return types.join(" or ");
+        }
+    }
+    if let Some(JsonValue::Array(schemas)) = map.get("allOf") {
+        let types: Vec<_> = schemas
+            .iter()
+            .map(|schema| type_name(anchors, schema))
+            .collect();
+        return types.join(" and ");
+    }
+
+    match map.get("type") {
+        Some(JsonValue::String(ty)) => json_type_name(anchors, ty, map),
+        Some(JsonValue::Array(types)) => {
+            let types: Vec<_> = types
+                .iter()
+                .filter_map(JsonValue::as_str)
+                .map(|ty| json_type_name(anchors, ty, map))
+                .collect();
+            types.join(" or ")
+        }
+        _ if map.contains_key("properties") => json_type_name(anchors, "object", map),
+        _ if map.contains_key("items") || map.contains_key("prefixItems") => {
+            json_type_name(anchors, "array", map)
+        }
+        _ => "any".into(),
+    }
+}
+
+/// Returns a readable name of the JSON Schema type `ty`, described by `map`.
+fn json_type_name(anchors: &Anchors, ty: &str, map: &JsonMap<String, JsonValue>) -> String {
+    match ty {
+        "array" => match (map.get("prefixItems"), map.get("items")) {
+            (Some(JsonValue::Array(items)), _) | (None, Some(JsonValue::Array(items))) => {
+                let items: Vec<_> = items
+                    .iter()
+                    .map(|schema| type_name(anchors, schema))
+                    .collect();
+                format!("tuple of ({})", items.join(", "))
+            }
+            (_, Some(items)) => format!("array of {}", grouped(type_name(anchors, items))),
+            _ => "`array`".into(),
+        },
+        "object" => match (map.get("properties"), map.get("additionalProperties")) {
+            (None, Some(values)) if values.is_object() => {
+                format!("map of {}", grouped(type_name(anchors, values)))
+            }
+            _ => "`object`".into(),
+        },
+        ty => match map.get("format").and_then(JsonValue::as_str) {
+            Some(format) => format!("`{ty}` (`{format}`)"),
+            None => format!("`{ty}`"),
+        },
+    }
+}
+
+/// Wraps a type made of several others in parentheses, so that it reads as a whole.
+fn grouped(ty: String) -> String {
+    if ty.contains(" or ") || ty.contains(" and ") {
+        format!("({ty})")
+    } else {
+        ty
+    }
+}
+
+/// Returns the properties an object schema requires.
+fn required(schema: &JsonValue) -> Vec<&str> {
+    match schema.get("required") {
+        Some(JsonValue::Array(required)) => required.iter().filter_map(JsonValue::as_str).collect(),
+        _ => vec![],
+    }
+}
+
+/// Returns the description of a schema, if any.
+fn description(schema: &JsonValue) -> &str {
+    schema
+        .get("description")
+        .and_then(JsonValue::as_str)
+        .unwrap_or_default()
+}
+
+/// The anchors of the sections documenting each method and schema of a document,
+/// unique within its page.
+struct Anchors {
+    methods: BTreeMap<String, String>,
+    schemas: BTreeMap<String, String>,
+}
+
+impl Anchors {
+    /// Assigns the anchors of the methods, then of the schemas, of `doc`, suffixing
+    /// those already taken with the first number that makes them unique.
+    fn new(doc: &OpenRpc) -> Self {
+        let mut taken = BTreeSet::new();
+        let mut unique = |kind, name: &str| {
+            let base = anchor(kind, name);
+            let mut anchor = base.clone();
+            for i in 2.. {
+                if taken.insert(anchor.clone()) {
+                    break;
+                }
+                anchor = format!("{base}-{i}");
+            }
+            (name.to_string(), anchor)
+        };
+        let methods = doc
+            .methods
+            .iter()
+            .map(|method| unique("method", &method.name))
+            .collect();
+        let schemas = doc
+            .components
+            .schemas
+            .keys()
+            .map(|name| unique("schema", name))
+            .collect();
+        Self { methods, schemas }
+    }
+
+    /// Returns the anchor of the section documenting the method `name`.
+    fn method(&self, name: &str) -> String {
+        self.methods
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| anchor("method", name))
+    }
+
+    /// Returns the anchor of the section documenting the schema `name`.
+    fn schema(&self, name: &str) -> String {
+        self.schemas
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| anchor("schema", name))
+    }
+}
+
+/// Returns the anchor of the section documenting the method or schema `name`, before
+/// collisions are resolved.
+fn anchor(kind: &str, name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("{kind}-{slug}")
+}
+
+/// Escapes `text` for a table cell, which must fit on one line.
+fn cell(text: &str) -> String {
+    text.trim().replace('|', "\\|").replace('\n', "<br>")
+}
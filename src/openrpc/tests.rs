@@ -0,0 +1,1404 @@
+use std::collections::HashMap;
+
+use super::*;
+
+/// A method without parameters returning a `u64`, to build test methods from.
+const METHOD: RpcMethod = RpcMethod {
+    available: true,
+    aliases: &[],
+    tags: &[],
+    description: "Returns the block count.\n\nCounts every block.",
+    summary: None,
+    params: |_g| vec![],
+    result: |g| g.result_of::<u64>("getblockcount_result"),
+    deprecated: false,
+    replaced_by: None,
+    deprecation_note: None,
+    subscription: false,
+    unsubscribe: None,
+    cost: None,
+    result_examples: &[],
+    examples: &[],
+    errors: &[],
+    param_structure: ParamStructure::Either,
+    public_name: None,
+    overlay: None,
+};
+
+#[test]
+fn matches_name_and_aliases() {
+    let method = RpcMethod {
+        aliases: &["blockcount", "count"],
+        ..METHOD
+    }
+    .generate(&mut Generator::new(), "getblockcount");
+
+    assert!(method.matches_name("getblockcount"));
+    assert!(method.matches_name("blockcount"));
+    assert!(method.matches_name("count"));
+    assert!(!method.matches_name("getblock"));
+    assert!(!method.matches_name(""));
+}
+
+#[test]
+fn subscriptions_are_marked() {
+    let method = RpcMethod {
+        result: |g| g.item::<u64>("subscribeHeights_item"),
+        subscription: true,
+        unsubscribe: Some("unsubscribeHeights"),
+        ..METHOD
+    }
+    .generate(&mut Generator::new(), "subscribeHeights");
+
+    let method = serde_json::to_value(&method).unwrap();
+    assert_eq!(method["x-subscription"], true);
+    assert_eq!(method["x-unsubscribe"], "unsubscribeHeights");
+    assert_eq!(
+        method["result"]["schema"],
+        serde_json::json!({ "type": "integer", "format": "uint64", "minimum": 0 })
+    );
+}
+
+#[test]
+fn canonical_json_is_fixed() {
+    let method = RpcMethod {
+        params: |g| vec![g.param_with_default::<f64>("fee", "The fee.", false, &2.0)],
+        ..METHOD
+    };
+    let doc = OpenRpc::build(
+        Info::builder().title("Node").version("1.0.0").build(),
+        [(&"getblockcount", &method)],
+    );
+
+    // Keys are sorted and the whole-number default is written without a fraction.
+    assert_eq!(
+        doc.to_json_pretty().unwrap(),
+        r#"{
+  "components": {
+    "schemas": {}
+  },
+  "info": {
+    "description": "",
+    "title": "Node",
+    "version": "1.0.0"
+  },
+  "methods": [
+    {
+      "description": "Returns the block count.\n\nCounts every block.",
+      "name": "getblockcount",
+      "paramStructure": "either",
+      "params": [
+        {
+          "description": "The fee.",
+          "name": "fee",
+          "schema": {
+            "default": 2,
+            "format": "double",
+            "type": "number"
+          },
+          "summary": "The fee."
+        }
+      ],
+      "result": {
+        "description": "",
+        "name": "getblockcount_result",
+        "schema": {
+          "format": "uint64",
+          "minimum": 0,
+          "type": "integer"
+        },
+        "summary": ""
+      },
+      "summary": "Returns the block count."
+    }
+  ],
+  "openrpc": "1.3.2"
+}
+"#
+    );
+}
+
+#[test]
+fn traits_share_components() {
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Balance {
+        amount: u64,
+    }
+
+    let wallet = RpcMethod {
+        tags: &["Wallet"],
+        result: |g| g.result_of::<Balance>("getbalance_result"),
+        ..METHOD
+    };
+    let network = RpcMethod {
+        tags: &["Network"],
+        result: |g| g.result_of::<Balance>("getpeerbalance_result"),
+        ..METHOD
+    };
+    let doc = OpenRpc::build(
+        Info::builder().title("Node").version("1.0.0").build(),
+        [(&"getbalance", &wallet), (&"getpeerbalance", &network)],
+    );
+
+    let doc = serde_json::to_value(&doc).unwrap();
+    let schemas = doc["components"]["schemas"].as_object().unwrap();
+    assert_eq!(schemas.keys().collect::<Vec<_>>(), ["Balance"]);
+    let tags = doc["components"]["tags"].as_object().unwrap();
+    assert_eq!(tags.keys().collect::<Vec<_>>(), ["Network", "Wallet"]);
+    for (method, tag) in doc["methods"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .zip(["Wallet", "Network"])
+    {
+        assert_eq!(
+            method["tags"],
+            serde_json::json!([{ "$ref": format!("#/components/tags/{tag}") }])
+        );
+        assert_eq!(
+            method["result"]["schema"],
+            serde_json::json!({ "$ref": "#/components/schemas/Balance" })
+        );
+    }
+}
+
+#[test]
+fn formats_override_the_schema() {
+    let method = RpcMethod {
+        params: |g| {
+            vec![
+                g.param::<String>("parent", "The parent hash.", true)
+                    .with_format("hex"),
+            ]
+        },
+        result: |g| {
+            g.result_of::<String>("getblockhash_result")
+                .with_format("hex")
+        },
+        ..METHOD
+    }
+    .generate(&mut Generator::new(), "getblockhash");
+
+    let method = serde_json::to_value(&method).unwrap();
+    let hex = serde_json::json!({ "type": "string", "format": "hex" });
+    assert_eq!(method["params"][0]["schema"], hex);
+    assert_eq!(method["result"]["schema"], hex);
+}
+
+#[cfg(feature = "validate")]
+#[test]
+fn versions_must_be_semver() {
+    let info = |version| Info::builder().title("Test").version(version).build();
+
+    assert_eq!(
+        validate::version(&info("1.2.3")).unwrap(),
+        semver::Version::new(1, 2, 3)
+    );
+    let err = validate::version(&info("1.0")).unwrap_err();
+    assert!(matches!(err, validate::Error::InvalidVersion { .. }));
+    assert!(
+        err.to_string()
+            .starts_with("info.version `1.0` is not a semantic version: ")
+    );
+}
+
+#[test]
+fn nullable_results_permit_null() {
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Block {
+        height: u64,
+    }
+
+    let mut generator = Generator::new();
+    let count = RpcMethod {
+        result: |g| g.result_of::<u64>("getblockcount_result").nullable(),
+        ..METHOD
+    }
+    .generate(&mut generator, "getblockcount");
+    let block = RpcMethod {
+        result: |g| g.result_of::<Block>("getblock_result").nullable(),
+        ..METHOD
+    }
+    .generate(&mut generator, "getblock");
+
+    assert_eq!(
+        serde_json::to_value(&count).unwrap()["result"]["schema"],
+        serde_json::json!({ "type": ["integer", "null"], "format": "uint64", "minimum": 0 })
+    );
+    assert_eq!(
+        serde_json::to_value(&block).unwrap()["result"]["schema"],
+        serde_json::json!({
+            "anyOf": [{ "$ref": "#/components/schemas/Block" }, { "type": "null" }]
+        })
+    );
+}
+
+#[test]
+fn methods_without_params_serialize_an_empty_array() {
+    let method = METHOD.generate(&mut Generator::new(), "getblockcount");
+    assert_eq!(
+        serde_json::to_value(&method).unwrap()["params"],
+        serde_json::json!([])
+    );
+}
+
+#[cfg(feature = "validate")]
+#[test]
+fn examples_must_match_their_schemas() {
+    let method = RpcMethod {
+        params: |g| vec![g.param::<u32>("height", "The height.", true)],
+        result_examples: &[("tip", "3")],
+        ..METHOD
+    };
+    let mut doc = OpenRpc::build(
+        Info::builder().title("Test").version("1.0.0").build(),
+        [(&"getblockcount", &method)],
+    );
+    validate::examples(&doc).unwrap();
+
+    doc.methods[0].examples.push(ExamplePairing {
+        name: "negative".into(),
+        params: vec![Example {
+            name: "height".into(),
+            value: serde_json::json!("tall"),
+        }],
+        result: Example {
+            name: "negative".into(),
+            value: serde_json::json!(-1),
+        },
+    });
+    let Err(validate::Error::InvalidExamples { violations }) = validate::examples(&doc) else {
+        panic!("mismatched examples are reported");
+    };
+    assert_eq!(
+        violations
+            .iter()
+            .map(|violation| violation.pointer.as_str())
+            .collect::<Vec<_>>(),
+        [
+            "/methods/0/examples/1/params/0/value",
+            "/methods/0/examples/1/result/value"
+        ]
+    );
+    assert!(violations[0].message.contains("param `height`"));
+}
+
+#[test]
+fn public_names_preserve_the_internal_name() {
+    let method = RpcMethod {
+        public_name: Some("getinfo"),
+        ..METHOD
+    }
+    .generate(&mut Generator::new(), "internal_getinfo");
+    assert!(method.matches_name("internal_getinfo"));
+
+    let method = serde_json::to_value(&method).unwrap();
+    assert_eq!(method["name"], "getinfo");
+    assert_eq!(method["x-internal-name"], "internal_getinfo");
+
+    let method =
+        serde_json::to_value(METHOD.generate(&mut Generator::new(), "getblockcount")).unwrap();
+    assert_eq!(method["name"], "getblockcount");
+    assert!(method.get("x-internal-name").is_none());
+}
+
+#[test]
+fn remote_shadows_provide_the_schema() {
+    // A shadow of `std::time::Duration`, which doesn't implement `JsonSchema`.
+    #[derive(JsonSchema)]
+    #[serde(remote = "std::time::Duration")]
+    #[allow(dead_code)]
+    struct DurationDef {
+        secs: u64,
+        nanos: u32,
+    }
+
+    let doc = OpenRpc::build(
+        Info::builder().title("Node").version("1.0.0").build(),
+        [(
+            &"gettimeout",
+            &RpcMethod {
+                result: |g| g.result_of::<DurationDef>("gettimeout_result"),
+                ..METHOD
+            },
+        )],
+    );
+    // The component is named after the remote type.
+    let doc = serde_json::to_value(&doc).unwrap();
+    let schemas = doc["components"]["schemas"].as_object().unwrap();
+    assert_eq!(schemas.keys().collect::<Vec<_>>(), ["Duration"]);
+    assert_eq!(
+        doc["methods"][0]["result"]["schema"],
+        serde_json::json!({ "$ref": "#/components/schemas/Duration" })
+    );
+}
+
+#[test]
+fn small_schemas_are_inlined() {
+    /// A transaction ID.
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct TxId(String);
+
+    /// An address label.
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Label(String);
+
+    /// An amount.
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Amount(u64);
+
+    /// A transaction.
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Transaction {
+        txid: TxId,
+        amount: Amount,
+    }
+
+    let get_transaction = RpcMethod {
+        params: |g| vec![g.param::<TxId>("txid", "The transaction ID.", true)],
+        result: |g| g.result_of::<Transaction>("gettransaction_result"),
+        ..METHOD
+    };
+    let set_label = RpcMethod {
+        params: |g| vec![g.param::<Label>("label", "The label.", true)],
+        ..METHOD
+    };
+    let build = || {
+        OpenRpc::build(
+            Info::builder().title("Wallet").version("1.0.0").build(),
+            [
+                (&"gettransaction", &get_transaction),
+                (&"setlabel", &set_label),
+            ],
+        )
+    };
+
+    // `Amount` consists of 5 values, `Label` and `TxId` of 3.
+    let mut doc = build();
+    doc.inline_small_schemas(5);
+    assert_eq!(
+        doc.components.schemas.keys().collect::<Vec<_>>(),
+        ["Transaction", "TxId"]
+    );
+    let value = serde_json::to_value(&doc).unwrap();
+    // Schemas referenced once are inlined, from a parameter or another schema.
+    assert_eq!(
+        value["methods"][1]["params"][0]["schema"],
+        serde_json::json!({ "description": "An address label.", "type": "string" })
+    );
+    assert_eq!(
+        doc.components.schemas["Transaction"]["properties"]["amount"],
+        serde_json::json!({
+            "description": "An amount.",
+            "type": "integer",
+            "format": "uint64",
+            "minimum": 0,
+        })
+    );
+    // Those referenced twice stay shared.
+    assert_eq!(
+        value["methods"][0]["params"][0]["schema"],
+        serde_json::json!({ "$ref": "#/components/schemas/TxId" })
+    );
+    assert_eq!(
+        doc.components.schemas["Transaction"]["properties"]["txid"],
+        serde_json::json!({ "$ref": "#/components/schemas/TxId" })
+    );
+    #[cfg(feature = "validate")]
+    validate::document(&doc).unwrap();
+
+    // Larger schemas stay in the components.
+    let mut doc = build();
+    doc.inline_small_schemas(4);
+    assert_eq!(
+        doc.components.schemas.keys().collect::<Vec<_>>(),
+        ["Amount", "Transaction", "TxId"]
+    );
+}
+
+#[test]
+fn documents_are_written_to_files() {
+    let dir = std::env::temp_dir().join(format!("openrpsee-{}-write", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("openrpc.json");
+    fs::write(&path, "stale").unwrap();
+
+    let doc = OpenRpc::build(
+        Info::builder().title("Node").version("1.0.0").build(),
+        [(&"getblockcount", &METHOD)],
+    );
+    doc.write_to_file(&path).unwrap();
+
+    let written = fs::read_to_string(&path).unwrap();
+    assert_eq!(written, doc.to_json_pretty().unwrap());
+    let parsed: JsonValue = serde_json::from_str(&written).unwrap();
+    assert_eq!(parsed, serde_json::to_value(&doc).unwrap());
+    // The temporary file was renamed over the old one.
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+}
+
+#[test]
+fn param_examples_go_into_the_schema() {
+    let mut generator = Generator::new();
+    let descriptor = generator.param_with_example::<String>(
+        "address",
+        "The address.",
+        true,
+        &"t1Kz5pVz".to_string(),
+    );
+
+    let descriptor = serde_json::to_value(&descriptor).unwrap();
+    assert_eq!(
+        descriptor["schema"],
+        serde_json::json!({ "type": "string", "examples": ["t1Kz5pVz"] })
+    );
+    assert!(descriptor.get("example").is_none());
+    assert!(descriptor.get("examples").is_none());
+
+    let method = RpcMethod {
+        params: |g| {
+            vec![g.param_with_example::<String>(
+                "address",
+                "The address.",
+                true,
+                &"t1Kz5pVz".to_string(),
+            )]
+        },
+        ..METHOD
+    };
+    let doc = OpenRpc::build(
+        Info::builder().title("Test").version("1.0.0").build(),
+        [(&"getbalance", &method)],
+    );
+    let value = serde_json::to_value(&doc).unwrap();
+    assert_eq!(value["methods"][0]["params"][0], descriptor);
+    #[cfg(feature = "validate")]
+    validate::document(&doc).unwrap();
+}
+
+#[test]
+fn code_blocks_survive_in_descriptions() {
+    let method = RpcMethod {
+        description: concat!(
+            "Returns a block.\n",
+            "\n",
+            "```json\n",
+            "{\n",
+            "  \"hash\": \"00ab\"\n",
+            "}\n",
+            "```\n",
+        ),
+        ..METHOD
+    }
+    .generate(&mut Generator::new(), "getblock");
+
+    let method = serde_json::to_value(&method).unwrap();
+    assert_eq!(method["summary"], "Returns a block.");
+    assert_eq!(
+        method["description"],
+        "Returns a block.\n\n```json\n{\n  \"hash\": \"00ab\"\n}\n```"
+    );
+}
+
+#[test]
+fn response_envelopes_are_opt_in() {
+    let info = || Info::builder().title("Node").version("1.0.0").build();
+    let doc = OpenRpc::build(info(), [(&"getblockcount", &METHOD)]);
+    assert!(doc.components.schemas.is_empty());
+
+    let doc = OpenRpc::build_with(
+        Generator::builder().response_envelope(true).build(),
+        info(),
+        [(&"getblockcount", &METHOD)],
+    );
+    assert_eq!(
+        doc.components.schemas.keys().collect::<Vec<_>>(),
+        ["JsonRpcError", "JsonRpcResponse"]
+    );
+    assert_eq!(
+        doc.components.schemas["JsonRpcResponse"]["oneOf"][1]["properties"]["error"],
+        serde_json::json!({ "$ref": "#/components/schemas/JsonRpcError" })
+    );
+    #[cfg(feature = "validate")]
+    validate::document(&doc).unwrap();
+
+    let doc = OpenRpc::build_with(
+        Generator::builder()
+            .definitions_path("#/$defs/")
+            .response_envelope(true)
+            .build(),
+        info(),
+        [(&"getblockcount", &METHOD)],
+    );
+    assert_eq!(
+        doc.components.schemas["JsonRpcResponse"]["oneOf"][1]["properties"]["error"],
+        serde_json::json!({ "$ref": "#/$defs/JsonRpcError" })
+    );
+}
+
+#[test]
+fn identical_params_are_shared() {
+    let balance = RpcMethod {
+        params: |g| vec![g.param::<String>("account", "The account.", true)],
+        ..METHOD
+    };
+    let list = RpcMethod {
+        params: |g| {
+            vec![
+                g.param::<String>("account", "The account.", true),
+                g.param::<u32>("count", "The number of transactions.", false),
+            ]
+        },
+        ..METHOD
+    };
+    let send = RpcMethod {
+        params: |g| vec![g.param::<String>("account", "The sending account.", true)],
+        ..METHOD
+    };
+    let mut doc = OpenRpc::build(
+        Info::builder().title("Wallet").version("1.0.0").build(),
+        [
+            (&"getbalance", &balance),
+            (&"listtransactions", &list),
+            (&"sendmany", &send),
+            (&"sendfrom", &send),
+        ],
+    );
+    doc.share_content_descriptors();
+
+    let shared = serde_json::json!({ "$ref": "#/components/contentDescriptors/account" });
+    let renamed = serde_json::json!({ "$ref": "#/components/contentDescriptors/account_2" });
+    let value = serde_json::to_value(&doc).unwrap();
+    let params = |method: usize| &value["methods"][method]["params"];
+    assert_eq!(params(0), &serde_json::json!([shared]));
+    assert_eq!(params(1)[0], shared);
+    // A descriptor used once stays inline.
+    assert_eq!(params(1)[1]["name"], "count");
+    // Differing descriptors of the same name are shared under numbered names.
+    assert_eq!(params(2), &serde_json::json!([renamed]));
+    assert_eq!(params(3), &serde_json::json!([renamed]));
+
+    let descriptors = value["components"]["contentDescriptors"]
+        .as_object()
+        .unwrap();
+    assert_eq!(
+        descriptors.keys().collect::<Vec<_>>(),
+        ["account", "account_2"]
+    );
+    assert_eq!(descriptors["account"]["description"], "The account.");
+    assert_eq!(
+        descriptors["account_2"]["description"],
+        "The sending account."
+    );
+    #[cfg(feature = "validate")]
+    validate::document(&doc).unwrap();
+
+    // Without it, every param stays inline.
+    let doc = OpenRpc::build(
+        Info::builder().title("Wallet").version("1.0.0").build(),
+        [(&"getbalance", &balance), (&"listtransactions", &list)],
+    );
+    let value = serde_json::to_value(&doc).unwrap();
+    assert_eq!(value["methods"][0]["params"][0]["name"], "account");
+    assert!(value["components"].get("contentDescriptors").is_none());
+}
+
+/// Builds a document of `methods`, to compare with [`diff`].
+fn diff_document<'a>(
+    methods: impl IntoIterator<Item = (&'a &'static str, &'a RpcMethod)>,
+) -> OpenRpc {
+    OpenRpc::build(
+        Info::builder().title("Node").version("1.0.0").build(),
+        methods,
+    )
+}
+
+/// Returns the change of `kind` to `method`, or to its parameter `param`.
+fn change(method: &str, param: Option<&str>, kind: ChangeKind, breaking: bool) -> Change {
+    Change {
+        method: method.into(),
+        param: param.map(Into::into),
+        kind,
+        breaking,
+    }
+}
+
+#[test]
+fn diff_reports_removed_and_added_methods() {
+    let old = diff_document([(&"getblockcount", &METHOD), (&"getinfo", &METHOD)]);
+    let new = diff_document([(&"getblockcount", &METHOD), (&"getpeerinfo", &METHOD)]);
+
+    let report = diff(&old, &new);
+    assert_eq!(
+        report.changes,
+        [
+            change("getinfo", None, ChangeKind::MethodRemoved, true),
+            change("getpeerinfo", None, ChangeKind::MethodAdded, false),
+        ]
+    );
+    assert!(report.is_breaking());
+    assert_eq!(
+        report.breaking_changes().collect::<Vec<_>>(),
+        [&report.changes[0]]
+    );
+    assert_eq!(
+        report.to_string(),
+        "breaking: method `getinfo` was removed\n\
+         non-breaking: method `getpeerinfo` was added"
+    );
+
+    let report = diff(&old, &old);
+    assert!(report.changes.is_empty());
+    assert!(!report.is_breaking());
+    assert_eq!(report.to_string(), "no changes");
+}
+
+#[test]
+fn diff_classifies_param_changes() {
+    let old = RpcMethod {
+        params: |g| {
+            vec![
+                g.param::<u64>("amount", "The amount.", true),
+                g.param::<u32>("minconf", "The confirmations.", false),
+                g.param::<String>("comment", "The comment.", false),
+            ]
+        },
+        ..METHOD
+    };
+    let new = RpcMethod {
+        params: |g| {
+            vec![
+                g.param::<u64>("amount", "The amount.", false),
+                g.param::<u32>("minconf", "The confirmations.", true),
+                g.param::<u64>("fee", "The fee.", false),
+                g.param::<String>("account", "The account.", true),
+            ]
+        },
+        ..METHOD
+    };
+
+    let report = diff(
+        &diff_document([(&"send", &old)]),
+        &diff_document([(&"send", &new)]),
+    );
+    assert_eq!(
+        report.changes,
+        [
+            change(
+                "send",
+                Some("amount"),
+                ChangeKind::ParamBecameOptional,
+                false
+            ),
+            change(
+                "send",
+                Some("minconf"),
+                ChangeKind::ParamBecameRequired,
+                true
+            ),
+            change("send", Some("comment"), ChangeKind::ParamRemoved, true),
+            change("send", Some("fee"), ChangeKind::ParamAdded, false),
+            change("send", Some("account"), ChangeKind::ParamAdded, true),
+        ]
+    );
+    assert_eq!(
+        serde_json::to_value(&report.changes[0]).unwrap(),
+        serde_json::json!({
+            "method": "send",
+            "param": "amount",
+            "kind": "param-became-optional",
+            "breaking": false,
+        })
+    );
+}
+
+#[test]
+fn diff_reports_deprecations_and_result_changes() {
+    let deprecated = RpcMethod {
+        deprecated: true,
+        ..METHOD
+    };
+    let narrowed = RpcMethod {
+        result: |g| g.result_of::<u32>("getblockcount_result"),
+        ..METHOD
+    };
+    let old = diff_document([(&"getblockcount", &METHOD), (&"getinfo", &METHOD)]);
+    let new = diff_document([(&"getblockcount", &narrowed), (&"getinfo", &deprecated)]);
+
+    let report = diff(&old, &new);
+    assert_eq!(
+        report.changes,
+        [
+            change("getblockcount", None, ChangeKind::ResultSchemaChanged, true),
+            change("getinfo", None, ChangeKind::MethodDeprecated, false),
+        ]
+    );
+    // Deprecating an already deprecated method isn't a change.
+    assert!(diff(&new, &new).changes.is_empty());
+}
+
+#[test]
+fn diff_follows_renames_that_keep_an_alias() {
+    let renamed = RpcMethod {
+        aliases: &["getinfo"],
+        ..METHOD
+    };
+    let old = diff_document([(&"getinfo", &METHOD)]);
+    let new = diff_document([(&"getinformation", &renamed)]);
+
+    let report = diff(&old, &new);
+    assert!(report.changes.is_empty());
+    assert!(!report.is_breaking());
+
+    // Without the alias, the rename removes the old method.
+    let new = diff_document([(&"getinformation", &METHOD)]);
+    assert_eq!(
+        diff(&old, &new).changes,
+        [
+            change("getinfo", None, ChangeKind::MethodRemoved, true),
+            change("getinformation", None, ChangeKind::MethodAdded, false),
+        ]
+    );
+}
+
+#[test]
+fn unreferenced_schemas_are_pruned() {
+    /// An amount.
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Amount(u64);
+
+    /// A balance.
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Balance {
+        confirmed: Amount,
+    }
+
+    let method = RpcMethod {
+        result: |g| g.result_of::<Balance>("getbalance_result"),
+        ..METHOD
+    };
+    let mut doc = OpenRpc::build_with(
+        Generator::builder().response_envelope(true).build(),
+        Info::builder().title("Wallet").version("1.0.0").build(),
+        [(&"getbalance", &method)],
+    );
+    assert_eq!(
+        doc.components.schemas.keys().collect::<Vec<_>>(),
+        ["Amount", "Balance", "JsonRpcError", "JsonRpcResponse"]
+    );
+
+    // `Amount` is only referenced through `Balance`, and the envelope not at all.
+    doc.prune_unreferenced_schemas();
+    assert_eq!(
+        doc.components.schemas.keys().collect::<Vec<_>>(),
+        ["Amount", "Balance"]
+    );
+    #[cfg(feature = "validate")]
+    validate::document(&doc).unwrap();
+}
+
+#[test]
+fn terms_of_service_and_external_docs_use_spec_keys() {
+    let mut doc = OpenRpc::build(
+        Info::builder()
+            .title("Node")
+            .version("1.0.0")
+            .terms_of_service("https://example.com/terms")
+            .build(),
+        [(&"getblockcount", &METHOD)],
+    );
+    doc.external_docs = Some(ExternalDocs {
+        description: Some("The user guide.".into()),
+        url: "https://example.com/guide".into(),
+    });
+
+    let doc = serde_json::to_value(&doc).unwrap();
+    assert_eq!(doc["info"]["termsOfService"], "https://example.com/terms");
+    assert_eq!(
+        doc["externalDocs"],
+        serde_json::json!({ "description": "The user guide.", "url": "https://example.com/guide" })
+    );
+
+    // Neither is written when unset.
+    let doc = OpenRpc::build(
+        Info::builder().title("Node").version("1.0.0").build(),
+        [(&"getblockcount", &METHOD)],
+    );
+    let doc = serde_json::to_value(&doc).unwrap();
+    assert!(doc["info"].get("termsOfService").is_none());
+    assert!(doc.get("externalDocs").is_none());
+}
+
+#[test]
+fn unavailable_methods_are_left_out() {
+    let unavailable = RpcMethod {
+        available: false,
+        result: |g| g.item::<()>("unavailable"),
+        ..METHOD
+    };
+    let doc = OpenRpc::build(
+        Info::builder().title("Node").version("1.0.0").build(),
+        [(&"getblockcount", &METHOD), (&"stake", &unavailable)],
+    );
+
+    let doc = serde_json::to_value(&doc).unwrap();
+    let names: Vec<_> = doc["methods"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|method| &method["name"])
+        .collect();
+    assert_eq!(names, ["getblockcount"]);
+}
+
+/// Compares building a document of 50 methods with reusing a cached one.
+///
+/// Run with `cargo test --release -- --ignored --nocapture cached_document_timing`.
+#[test]
+#[ignore = "timing comparison, run manually"]
+fn cached_document_timing() {
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Header {
+        height: u32,
+        time: u64,
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Block {
+        hash: String,
+        header: Header,
+        transactions: Vec<String>,
+    }
+
+    const ITERATIONS: u32 = 200;
+
+    let method = RpcMethod {
+        params: |g| {
+            vec![
+                g.param::<String>("hash", "The block hash.", true),
+                g.param::<Option<u8>>("verbosity", "The verbosity.", false),
+            ]
+        },
+        result: |g| g.result_of::<Block>("getblock_result"),
+        ..METHOD
+    };
+    let names: Vec<&'static str> = (0..50)
+        .map(|i| &*Box::leak(format!("getblock{i}").into_boxed_str()))
+        .collect();
+    let methods = || names.iter().zip(std::iter::repeat(&method));
+    let info = || Info::builder().title("Node").version("1.0.0").build();
+
+    let start = std::time::Instant::now();
+    for _ in 0..ITERATIONS {
+        std::hint::black_box(OpenRpc::build(info(), methods()));
+    }
+    let built = start.elapsed() / ITERATIONS;
+
+    let cache = CachedDocument::new();
+    let start = std::time::Instant::now();
+    for _ in 0..ITERATIONS {
+        std::hint::black_box(cache.get_or_build(info, methods()));
+    }
+    let cached = start.elapsed() / ITERATIONS;
+
+    println!("OpenRpc::build: {built:?} per call, CachedDocument: {cached:?} per call");
+    assert!(cached < built);
+}
+
+#[test]
+fn maps_have_additional_properties() {
+    let method = RpcMethod {
+        params: |g| vec![g.param::<HashMap<String, u64>>("minimums", "The minimums.", true)],
+        result: |g| g.result_of::<BTreeMap<String, u64>>("getbalances_result"),
+        ..METHOD
+    }
+    .generate(&mut Generator::new(), "getbalances");
+
+    let method = serde_json::to_value(&method).unwrap();
+    let map = serde_json::json!({
+        "type": "object",
+        "additionalProperties": { "type": "integer", "format": "uint64", "minimum": 0 }
+    });
+    assert_eq!(method["params"][0]["schema"], map);
+    assert_eq!(method["result"]["schema"], map);
+}
+
+#[test]
+fn nullable_params_accept_null() {
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Filter {
+        min_height: u32,
+    }
+
+    let method = RpcMethod {
+        params: |g| {
+            vec![
+                g.param::<u8>("verbosity", "The verbosity.", false)
+                    .nullable(),
+                g.param::<Filter>("filter", "The filter.", false).nullable(),
+            ]
+        },
+        ..METHOD
+    }
+    .generate(&mut Generator::new(), "getblock");
+
+    let method = serde_json::to_value(&method).unwrap();
+    assert_eq!(method["params"][0]["required"], JsonValue::Null);
+    assert_eq!(
+        method["params"][0]["schema"],
+        serde_json::json!({ "type": ["integer", "null"], "format": "uint8", "minimum": 0, "maximum": 255 })
+    );
+    #[cfg(feature = "validate")]
+    assert!(jsonschema::is_valid(
+        &method["params"][0]["schema"],
+        &JsonValue::Null
+    ));
+    assert_eq!(
+        method["params"][1]["schema"],
+        serde_json::json!({
+            "anyOf": [{ "$ref": "#/components/schemas/Filter" }, { "type": "null" }]
+        })
+    );
+}
+
+#[test]
+fn tuples_are_fixed_length_arrays() {
+    let method = RpcMethod {
+        params: |g| vec![g.param::<(u64, u64)>("range", "The range.", true)],
+        result: |g| g.result_of::<(String, u64)>("getbestinrange_result"),
+        ..METHOD
+    }
+    .generate(&mut Generator::new(), "getbestinrange");
+
+    let method = serde_json::to_value(&method).unwrap();
+    let uint64 = serde_json::json!({ "type": "integer", "format": "uint64", "minimum": 0 });
+    assert_eq!(
+        method["params"][0]["schema"],
+        serde_json::json!({
+            "type": "array",
+            "items": [uint64, uint64],
+            "minItems": 2,
+            "maxItems": 2
+        })
+    );
+    assert_eq!(
+        method["result"]["schema"],
+        serde_json::json!({
+            "type": "array",
+            "items": [{ "type": "string" }, uint64],
+            "minItems": 2,
+            "maxItems": 2
+        })
+    );
+}
+
+/// A document with a reference, an optional field, a string enum and a property name
+/// that isn't an identifier, to render or generate code from.
+#[cfg(any(feature = "codegen", feature = "render"))]
+fn wallet_document() -> OpenRpc {
+    /// A wallet transaction.
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Transaction {
+        /// The transaction ID.
+        #[serde(rename = "tx-id")]
+        tx_id: String,
+        /// The direction of the transaction.
+        kind: Kind,
+        /// The memo, if any.
+        memo: Option<String>,
+    }
+
+    /// The direction of a transaction.
+    #[derive(JsonSchema)]
+    #[serde(rename_all = "lowercase")]
+    #[allow(dead_code)]
+    enum Kind {
+        Send,
+        Receive,
+    }
+
+    let get_transaction = RpcMethod {
+        description: "Returns a transaction.",
+        params: |g| {
+            vec![
+                g.param::<String>("txid", "The transaction ID.", true),
+                g.param::<Option<bool>>("verbose", "Whether to | decode it.", false),
+            ]
+        },
+        result: |g| g.result_of::<Transaction>("gettransaction_result"),
+        ..METHOD
+    };
+    let get_memo = RpcMethod {
+        description: "Returns the memo of a transaction.\n\nThe memo may be empty.",
+        params: |g| {
+            vec![
+                g.param::<String>("txid", "The transaction ID.", true),
+                g.param::<bool>("raw", "Whether to return\nthe raw bytes.", false),
+            ]
+        },
+        result: |g| g.result_of::<Option<String>>("getmemo_result").nullable(),
+        deprecated: true,
+        deprecation_note: Some("Use `gettransaction`."),
+        ..METHOD
+    };
+    OpenRpc::build(
+        Info::builder().title("Wallet").version("1.0.0").build(),
+        [
+            (&"gettransaction", &get_transaction),
+            (&"getmemo", &get_memo),
+        ],
+    )
+}
+
+#[cfg(feature = "codegen")]
+#[test]
+fn typescript_client_is_fixed() {
+    // The property that isn't an identifier is quoted, the optional field may also be
+    // left out, and the multi-line parameter description stays within the comment.
+    assert_eq!(
+        codegen::typescript(&wallet_document()),
+        r#"// Generated from the OpenRPC document of Wallet 1.0.0.
+
+/** The direction of a transaction. */
+export type Kind = "send" | "receive";
+
+/** A wallet transaction. */
+export interface Transaction {
+  /** The direction of the transaction. */
+  kind: Kind;
+  /** The memo, if any. */
+  memo?: string | null;
+  /** The transaction ID. */
+  "tx-id": string;
+}
+
+/** Sends JSON-RPC requests, resolving to their results. */
+export interface Transport {
+  request(method: string, params: unknown[] | Record<string, unknown>): Promise<unknown>;
+}
+
+/** Drops the trailing parameters that weren't given, leaving them to their defaults. */
+function positional(params: unknown[]): unknown[] {
+  let end = params.length;
+  while (end > 0 && params[end - 1] === undefined) {
+    end--;
+  }
+  return params.slice(0, end);
+}
+
+/** A client of the Wallet API. */
+export class Client {
+  constructor(private readonly transport: Transport) {}
+
+  /**
+   * Returns the memo of a transaction.
+   *
+   * The memo may be empty.
+   *
+   * @param txid The transaction ID.
+   * @param raw Whether to return
+   * the raw bytes.
+   * @deprecated Use `gettransaction`.
+   */
+  getmemo(txid: string, raw?: boolean): Promise<string | null> {
+    return this.transport.request("getmemo", positional([txid, raw])) as Promise<string | null>;
+  }
+
+  /**
+   * Returns a transaction.
+   *
+   * @param txid The transaction ID.
+   * @param verbose Whether to | decode it.
+   */
+  gettransaction(txid: string, verbose?: boolean | null): Promise<Transaction> {
+    return this.transport.request("gettransaction", positional([txid, verbose])) as Promise<Transaction>;
+  }
+}
+"#
+    );
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn markdown_page_is_fixed() {
+    // Pipes and newlines are escaped within table cells.
+    assert_eq!(
+        render::markdown(&wallet_document()),
+        r#"# Wallet
+
+Version 1.0.0
+
+## Methods
+
+- [`getmemo`](#method-getmemo): Returns the memo of a transaction.
+- [`gettransaction`](#method-gettransaction): Returns a transaction.
+
+<a id="method-getmemo"></a>
+
+### `getmemo`
+
+> **Deprecated.** Use `gettransaction`.
+
+Returns the memo of a transaction.
+
+The memo may be empty.
+
+**Parameters**
+
+| Name | Type | Required | Description |
+| --- | --- | --- | --- |
+| `txid` | `string` | Yes | The transaction ID. |
+| `raw` | `boolean` | No | Whether to return<br>the raw bytes. |
+
+**Result**: `string` or `null`
+
+<a id="method-gettransaction"></a>
+
+### `gettransaction`
+
+Returns a transaction.
+
+**Parameters**
+
+| Name | Type | Required | Description |
+| --- | --- | --- | --- |
+| `txid` | `string` | Yes | The transaction ID. |
+| `verbose` | `boolean` or `null` | No | Whether to \| decode it. |
+
+**Result**: [`Transaction`](#schema-transaction)
+
+A wallet transaction.
+
+## Schemas
+
+<a id="schema-kind"></a>
+
+### `Kind`
+
+The direction of a transaction.
+
+**Type**: one of `"send"`, `"receive"`
+
+<a id="schema-transaction"></a>
+
+### `Transaction`
+
+A wallet transaction.
+
+| Property | Type | Required | Description |
+| --- | --- | --- | --- |
+| `kind` | [`Kind`](#schema-kind) | Yes | The direction of the transaction. |
+| `memo` | `string` or `null` | No | The memo, if any. |
+| `tx-id` | `string` | Yes | The transaction ID. |
+"#
+    );
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn markdown_anchors_are_unique() {
+    let deprecated = RpcMethod {
+        deprecated: true,
+        ..METHOD
+    };
+    let doc = OpenRpc::build(
+        Info::builder().title("Node").version("1.0.0").build(),
+        [
+            (&"getInfo", &METHOD),
+            (&"getinfo", &deprecated),
+            (&"get.info", &METHOD),
+            (&"get-info", &METHOD),
+        ],
+    );
+
+    let page = render::markdown(&doc);
+    assert!(page.contains(
+        "- [`get-info`](#method-get-info): Returns the block count.
+- [`get.info`](#method-get-info-2): Returns the block count.
+- [`getInfo`](#method-getinfo): Returns the block count.
+- [`getinfo`](#method-getinfo-2): Returns the block count.
+"
+    ));
+    for (anchor, name) in [
+        ("method-get-info", "get-info"),
+        ("method-get-info-2", "get.info"),
+        ("method-getinfo", "getInfo"),
+        ("method-getinfo-2", "getinfo"),
+    ] {
+        assert!(page.contains(&format!("<a id=\"{anchor}\"></a>\n\n### `{name}`\n")));
+    }
+    // Without a note, the deprecation stands on its own.
+    assert!(page.contains("### `getinfo`\n\n> **Deprecated.**\n\nReturns the block count."));
+}
+
+#[test]
+fn results_of_the_same_schema_are_shared() {
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Balance {
+        amount: u64,
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct WalletInfo {
+        version: u32,
+    }
+
+    let balance = RpcMethod {
+        result: |g| g.result_of::<Balance>("getbalance_result"),
+        ..METHOD
+    };
+    let unconfirmed = RpcMethod {
+        result: |g| g.result_of::<Balance>("getunconfirmedbalance_result"),
+        ..METHOD
+    };
+    let info = RpcMethod {
+        result: |g| g.result_of::<WalletInfo>("getwalletinfo_result"),
+        ..METHOD
+    };
+    let mut doc = OpenRpc::build(
+        Info::builder().title("Wallet").version("1.0.0").build(),
+        [
+            (&"getbalance", &balance),
+            (&"getblockcount", &METHOD),
+            (&"getunconfirmedbalance", &unconfirmed),
+            (&"getwalletinfo", &info),
+        ],
+    );
+    doc.share_result_descriptors();
+
+    let value = serde_json::to_value(&doc).unwrap();
+    let shared = serde_json::json!({ "$ref": "#/components/contentDescriptors/Balance" });
+    assert_eq!(value["methods"][0]["result"], shared);
+    assert_eq!(value["methods"][2]["result"], shared);
+    // Results that aren't shared, or whose schema isn't a component, stay inline.
+    assert_eq!(
+        value["methods"][1]["result"]["name"],
+        "getblockcount_result"
+    );
+    assert_eq!(
+        value["methods"][3]["result"]["name"],
+        "getwalletinfo_result"
+    );
+
+    let descriptors = value["components"]["contentDescriptors"]
+        .as_object()
+        .unwrap();
+    assert_eq!(descriptors.keys().collect::<Vec<_>>(), ["Balance"]);
+    assert_eq!(descriptors["Balance"]["name"], "Balance");
+    assert_eq!(
+        descriptors["Balance"]["schema"],
+        serde_json::json!({ "$ref": "#/components/schemas/Balance" })
+    );
+    #[cfg(feature = "validate")]
+    validate::document(&doc).unwrap();
+}
+
+#[test]
+fn documents_serialize_identically() {
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Account {
+        balance: u64,
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Zone {
+        name: String,
+    }
+
+    let build = || {
+        OpenRpc::build(
+            Info::builder().title("Node").version("1.0.0").build(),
+            [
+                (
+                    &"listaccounts",
+                    &RpcMethod {
+                        params: |g| {
+                            vec![g.param::<HashMap<String, u64>>("filter", "The filter.", true)]
+                        },
+                        result: |g| g.result_of::<Account>("listaccounts_result"),
+                        ..METHOD
+                    },
+                ),
+                // Generated first, as methods are generated in order of their names.
+                (
+                    &"getzone",
+                    &RpcMethod {
+                        result: |g| g.result_of::<Zone>("getzone_result"),
+                        ..METHOD
+                    },
+                ),
+            ],
+        )
+    };
+
+    let first = build().to_json_pretty().unwrap();
+    assert_eq!(first, build().to_json_pretty().unwrap());
+    assert_eq!(
+        serde_json::to_string(&build()).unwrap(),
+        serde_json::to_string(&build()).unwrap()
+    );
+
+    // Schemas are sorted by name, not in the order they were generated in.
+    assert!(first.find("\"Account\": {").unwrap() < first.find("\"Zone\": {").unwrap());
+}
+
+#[test]
+fn deprecations_serialize_on_deprecated_params_only() {
+    let method = RpcMethod {
+        params: |g| {
+            vec![
+                g.param::<String>("address", "The address.", true),
+                g.param::<u32>("minconf", "The confirmations.", false)
+                    .deprecated(Some("Use `minheight`.")),
+                g.param::<bool>("verbose", "Whether to decode it.", false)
+                    .deprecated(None),
+            ]
+        },
+        ..METHOD
+    }
+    .generate(&mut Generator::new(), "getbalance");
+
+    let method = serde_json::to_value(&method).unwrap();
+    let params = method["params"].as_array().unwrap();
+    assert!(params[0].get("deprecated").is_none());
+    assert!(params[0].get("x-deprecation-note").is_none());
+    assert_eq!(params[1]["deprecated"], true);
+    assert_eq!(params[1]["x-deprecation-note"], "Use `minheight`.");
+    assert_eq!(params[2]["deprecated"], true);
+    assert!(params[2].get("x-deprecation-note").is_none());
+    // The method itself isn't deprecated.
+    assert!(method.get("deprecated").is_none());
+}
+
+#[test]
+fn summaries_override_the_first_line() {
+    let method = RpcMethod {
+        summary: Some("Counts blocks."),
+        ..METHOD
+    }
+    .generate(&mut Generator::new(), "getblockcount");
+
+    let method = serde_json::to_value(&method).unwrap();
+    assert_eq!(method["summary"], "Counts blocks.");
+    assert_eq!(
+        method["description"],
+        "Returns the block count.\n\nCounts every block."
+    );
+}
@@ -0,0 +1,375 @@
+//! Validation of OpenRPC documents.
+
+use std::fmt;
+
+use schemars::Schema;
+use serde_json::Value as JsonValue;
+
+use super::{Info, OpenRpc, RpcMethod};
+
+/// The bundled meta-schemas, by the OpenRPC version they describe, oldest first.
+const META_SCHEMAS: &[(&str, &str)] = &[
+    ("1.2.6", include_str!("meta-schema/1.2.6.json")),
+    ("1.3.2", include_str!("meta-schema/1.3.2.json")),
+];
+
+/// An error found while validating an OpenRPC document.
+#[derive(Debug)]
+pub enum Error {
+    /// `info.version` is not a valid semantic version.
+    InvalidVersion {
+        /// The offending version.
+        version: String,
+        /// Why it could not be parsed.
+        source: semver::Error,
+    },
+    /// No bundled meta-schema covers the document's `openrpc` version.
+    UnsupportedVersion {
+        /// The document's `openrpc` version.
+        version: String,
+    },
+    /// The document could not be serialized.
+    Serialize(serde_json::Error),
+    /// The document does not conform to the meta-schema, or has examples that don't
+    /// match their schemas.
+    MetaSchema {
+        /// The version of the meta-schema the document was validated against.
+        version: &'static str,
+        /// Every violation found.
+        violations: Vec<Violation>,
+    },
+    /// Some of the document's examples don't match their schemas.
+    InvalidExamples {
+        /// Every mismatch found.
+        violations: Vec<Violation>,
+    },
+    /// The document reads back from its serialized JSON as a different document.
+    RoundTrip,
+}
+
+/// A single problem found in a document.
+#[derive(Clone, Debug)]
+pub struct Violation {
+    /// The JSON Pointer of the offending value within the document.
+    pub pointer: String,
+    /// What is wrong with the value.
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pointer = if self.pointer.is_empty() {
+            "/"
+        } else {
+            &self.pointer
+        };
+        write!(f, "{pointer}: {}", self.message)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidVersion { version, source } => {
+                write!(
+                    f,
+                    "info.version `{version}` is not a semantic version: {source}"
+                )
+            }
+            Error::UnsupportedVersion { version } => {
+                write!(f, "no bundled meta-schema for OpenRPC version `{version}`")
+            }
+            Error::Serialize(e) => write!(f, "document could not be serialized: {e}"),
+            Error::MetaSchema {
+                version,
+                violations,
+            } => {
+                write!(f, "document is not a valid OpenRPC {version} document")?;
+                for violation in violations {
+                    write!(f, "\n  {violation}")?;
+                }
+                Ok(())
+            }
+            Error::InvalidExamples { violations } => {
+                write!(f, "document has examples that don't match their schemas")?;
+                for violation in violations {
+                    write!(f, "\n  {violation}")?;
+                }
+                Ok(())
+            }
+            Error::RoundTrip => write!(f, "document changed when read back from its JSON"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::InvalidVersion { source, .. } => Some(source),
+            Error::Serialize(e) => Some(e),
+            Error::UnsupportedVersion { .. }
+            | Error::MetaSchema { .. }
+            | Error::InvalidExamples { .. }
+            | Error::RoundTrip => None,
+        }
+    }
+}
+
+/// Checks that the API version parses as a semantic version.
+///
+/// Many API registries reject documents whose `info.version` isn't semver.
+pub fn version(info: &Info) -> Result<semver::Version, Error> {
+    semver::Version::parse(&info.version).map_err(|source| Error::InvalidVersion {
+        version: info.version.to_string(),
+        source,
+    })
+}
+
+/// Returns the bundled meta-schema for documents of the given OpenRPC version, along
+/// with the version it describes.
+///
+/// This is the oldest bundled meta-schema of the same major version that is not older
+/// than `openrpc`, so e.g. a `1.2.4` document uses the 1.2.6 meta-schema rather than
+/// the newest one.
+pub fn meta_schema(openrpc: &str) -> Result<(&'static str, JsonValue), Error> {
+    let unsupported = || Error::UnsupportedVersion {
+        version: openrpc.into(),
+    };
+    let requested = semver::Version::parse(openrpc).map_err(|_| unsupported())?;
+
+    META_SCHEMAS
+        .iter()
+        .find(|(version, _)| {
+            let version = semver::Version::parse(version).expect("valid bundled version");
+            version.major == requested.major && version >= requested
+        })
+        .map(|(version, schema)| {
+            let schema = serde_json::from_str(schema).expect("bundled meta-schemas are JSON");
+            (*version, schema)
+        })
+        .ok_or_else(unsupported)
+}
+
+/// Validates the document against the bundled meta-schema matching its `openrpc`
+/// version (see [`meta_schema`]).
+///
+/// This also checks the document's examples (see [`examples`]), and that the
+/// replacements of deprecated methods are part of the document.
+pub fn document(doc: &OpenRpc) -> Result<(), Error> {
+    let (version, schema) = meta_schema(&doc.openrpc)?;
+    let validator = jsonschema::draft7::new(&schema).expect("bundled meta-schemas are valid");
+    let instance = serde_json::to_value(doc).map_err(Error::Serialize)?;
+
+    let mut violations: Vec<_> = validator
+        .iter_errors(&instance)
+        .map(|e| Violation {
+            pointer: e.instance_path().to_string(),
+            message: e.to_string(),
+        })
+        .collect();
+
+    // Check that replacements of deprecated methods are part of the document.
+    for (i, method) in doc.methods.iter().enumerate() {
+        if let Some(replaced_by) = &method.replaced_by
+            && !doc.methods.iter().any(|m| m.matches_name(replaced_by))
+        {
+            violations.push(Violation {
+                pointer: format!("/methods/{i}/x-replaced-by"),
+                message: format!("replacement method `{replaced_by}` is not in the document"),
+            });
+        }
+    }
+
+    violations.append(&mut example_violations(doc, &instance["components"]));
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::MetaSchema {
+            version,
+            violations,
+        })
+    }
+}
+
+/// Assembles the document for the entries of a generated `METHODS` table, validates
+/// it, and checks that it reads back from its serialized JSON unchanged.
+///
+/// This is the "everything is wired correctly" check for a crate's own tests (e.g.
+/// with `assert_valid_document(METHODS.entries(), info)`), returning the document for
+/// further assertions. The document is assembled by [`OpenRpc::build`], and `info`'s
+/// version must be a semantic version (see [`version`]).
+pub fn assert_valid_document<'a>(
+    methods: impl IntoIterator<Item = (&'a &'static str, &'a RpcMethod)>,
+    info: Info,
+) -> Result<OpenRpc, Error> {
+    version(&info)?;
+
+    let doc = OpenRpc::build(info, methods);
+    document(&doc)?;
+
+    let value = serde_json::to_value(&doc).map_err(Error::Serialize)?;
+    let json = serde_json::to_string(&doc).map_err(Error::Serialize)?;
+    let read_back: JsonValue = serde_json::from_str(&json).map_err(Error::Serialize)?;
+    if read_back != value {
+        return Err(Error::RoundTrip);
+    }
+
+    Ok(doc)
+}
+
+/// Checks the document's examples against the schemas they illustrate.
+///
+/// This covers the parameters and results of method examples, and the `examples`
+/// keywords of parameter, result and component schemas (e.g. from
+/// `#[schemars(example = ...)]`). Schemas are only known once the documented types
+/// are compiled, so this is meant to run on the assembled document, e.g. in a test,
+/// to catch examples that went stale when a type changed.
+pub fn examples(doc: &OpenRpc) -> Result<(), Error> {
+    let components = serde_json::to_value(&doc.components).map_err(Error::Serialize)?;
+    let violations = example_violations(doc, &components);
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::InvalidExamples { violations })
+    }
+}
+
+fn example_violations(doc: &OpenRpc, components: &JsonValue) -> Vec<Violation> {
+    let mut violations = vec![];
+
+    // Schemas referencing external documents, such as the meta-schema `rpc.discover`
+    // returns, can't be retrieved while validating, so they accept any value.
+    let mut components = components.clone();
+    if let Some(JsonValue::Object(schemas)) = components.get_mut("schemas") {
+        for schema in schemas.values_mut() {
+            if let Some(JsonValue::String(reference)) = schema.get("$ref")
+                && !reference.starts_with('#')
+            {
+                *schema = serde_json::json!({});
+            }
+        }
+    }
+    let components = &components;
+
+    for (i, method) in doc.methods.iter().enumerate() {
+        let descriptors = method
+            .params
+            .iter()
+            .enumerate()
+            .map(|(k, param)| (format!("/methods/{i}/params/{k}"), "param", param))
+            .chain([(format!("/methods/{i}/result"), "result", &method.result)])
+            .map(|(path, kind, descriptor)| match &descriptor.shared_as {
+                // Shared descriptors are serialized once, in the components.
+                Some(name) => (
+                    format!("/components/contentDescriptors/{name}"),
+                    kind,
+                    descriptor,
+                ),
+                None => (path, kind, descriptor),
+            });
+        for (path, kind, descriptor) in descriptors {
+            check_schema_examples(
+                &descriptor.schema,
+                components,
+                &format!("{path}/schema"),
+                &format!("method `{}`, {kind} `{}`", method.name, descriptor.name),
+                &mut violations,
+            );
+        }
+
+        for (j, example) in method.examples.iter().enumerate() {
+            let context = format!("method `{}`, example `{}`", method.name, example.name);
+            for (k, param_example) in example.params.iter().enumerate() {
+                let pointer = format!("/methods/{i}/examples/{j}/params/{k}/value");
+                match method.params.iter().find(|p| p.name == param_example.name) {
+                    Some(param) => check_value(
+                        &param.schema,
+                        components,
+                        &param_example.value,
+                        &pointer,
+                        &format!("{context}, param `{}`", param.name),
+                        &mut violations,
+                    ),
+                    None => violations.push(Violation {
+                        pointer,
+                        message: format!("{context}: unknown param `{}`", param_example.name),
+                    }),
+                }
+            }
+            check_value(
+                &method.result.schema,
+                components,
+                &example.result.value,
+                &format!("/methods/{i}/examples/{j}/result/value"),
+                &format!("{context}, result"),
+                &mut violations,
+            );
+        }
+    }
+
+    for (name, schema) in &doc.components.schemas {
+        if let Ok(schema) = Schema::try_from(schema.clone()) {
+            check_schema_examples(
+                &schema,
+                components,
+                &format!(
+                    "/components/schemas/{}",
+                    name.replace('~', "~0").replace('/', "~1")
+                ),
+                &format!("schema `{name}`"),
+                &mut violations,
+            );
+        }
+    }
+
+    violations
+}
+
+/// Checks the values of the `examples` keyword of `schema` against the schema itself.
+fn check_schema_examples(
+    schema: &Schema,
+    components: &JsonValue,
+    pointer: &str,
+    context: &str,
+    violations: &mut Vec<Violation>,
+) {
+    if let Some(JsonValue::Array(examples)) = schema.get("examples") {
+        for (n, example) in examples.iter().enumerate() {
+            check_value(
+                schema,
+                components,
+                example,
+                &format!("{pointer}/examples/{n}"),
+                &format!("{context}, example {n}"),
+                violations,
+            );
+        }
+    }
+}
+
+/// Checks `value` against `schema`, resolving references within the document's
+/// `components`.
+fn check_value(
+    schema: &Schema,
+    components: &JsonValue,
+    value: &JsonValue,
+    pointer: &str,
+    context: &str,
+    violations: &mut Vec<Violation>,
+) {
+    let schema = serde_json::json!({
+        "allOf": [schema],
+        "components": components,
+    });
+    match jsonschema::draft7::new(&schema) {
+        Ok(validator) => violations.extend(validator.iter_errors(value).map(|e| Violation {
+            pointer: format!("{pointer}{}", e.instance_path()),
+            message: format!("{context}: {e}"),
+        })),
+        Err(e) => violations.push(Violation {
+            pointer: pointer.into(),
+            message: format!("{context}: schema cannot be used for validation: {e}"),
+        }),
+    }
+}
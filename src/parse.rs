@@ -0,0 +1,1509 @@
+//! Extraction of JSON-RPC method definitions from RPC traits.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use quote::ToTokens;
+use syn::meta::ParseNestedMeta;
+
+use crate::{
+    GenerateOptions, Source, emit_rerun_if_changed, normalize_type,
+    openrpc::{Cost, ParamStructure},
+};
+
+/// A JSON-RPC method extracted from an RPC trait.
+#[derive(Clone, Debug)]
+pub struct MethodInfo {
+    /// The name the method is called by.
+    pub command: String,
+    /// Alternative names the method can also be called by.
+    pub aliases: Vec<String>,
+    /// The lines of the method's doc comment.
+    pub doc_lines: Vec<String>,
+    /// The summary given by `#[openrpc(summary = "...")]`, documented instead of the
+    /// first line of the doc comment.
+    pub summary: Option<String>,
+    /// The first path segment of the method's return type.
+    pub module: String,
+    /// The method's JSON-RPC parameters.
+    pub params: Vec<ParamInfo>,
+    /// The method's return type.
+    pub result_ty: String,
+    /// The type used for the result's schema, if it could be determined from the
+    /// return type (e.g. `T` for `RpcResult<T>`, `Result<Option<T>, E>`, or
+    /// `ResponsePayload<'a, T>`).
+    pub result_schema_ty: Option<String>,
+    /// Whether the result is documented as possibly `null`, as requested or because
+    /// the result type is optional.
+    pub result_nullable: bool,
+    /// For subscriptions, the type of each notification item.
+    pub item: Option<String>,
+    /// The result or item type as written, if local type aliases in it were resolved
+    /// for its schema.
+    pub aliased_result_ty: Option<String>,
+    /// For subscriptions, the name of the unsubscribe method.
+    pub unsubscribe: Option<String>,
+    /// Whether the method is deprecated.
+    pub deprecated: bool,
+    /// Whether the method is marked `#[doc(hidden)]`.
+    pub doc_hidden: bool,
+    /// The name of the method replacing this deprecated one.
+    pub replaced_by: Option<String>,
+    /// The `note` of the method's `#[deprecated]` attribute, if any.
+    pub deprecation_note: Option<String>,
+    /// The name of the trait the method is defined in.
+    pub trait_name: String,
+    /// The name of the Rust method within its trait.
+    pub fn_name: String,
+    /// Hints about the cost of calling the method.
+    pub cost: Option<Cost>,
+    /// Named examples of the method's result, as `(name, JSON value)` pairs.
+    pub result_examples: Vec<(String, String)>,
+    /// Named examples of calls to the method, pairing parameters with a result.
+    pub examples: Vec<ExampleInfo>,
+    /// The errors the method is documented to return.
+    pub errors: Vec<ErrorInfo>,
+    /// How the method accepts its parameters.
+    pub param_structure: ParamStructure,
+    /// The names of the tags the method is grouped under, besides its trait.
+    pub tags: Vec<String>,
+    /// The `cfg` predicate under which the method and its types are available, if
+    /// any.
+    pub cfg: Option<String>,
+}
+
+/// A JSON-RPC parameter of a [`MethodInfo`].
+#[derive(Clone, Debug)]
+pub struct ParamInfo {
+    /// The name of the parameter.
+    pub name: String,
+    /// The name the parameter is documented under, if it is renamed through
+    /// `#[serde(rename = "...")]` or jsonrpsee's `#[argument(rename = "...")]`.
+    pub rename: Option<String>,
+    /// The type used for the parameter's schema.
+    pub schema_ty: String,
+    /// The type as written, if local type aliases in it were resolved for its schema.
+    pub aliased_ty: Option<String>,
+    /// Whether the parameter is required, if it is stated by an attribute or can be
+    /// determined from its type.
+    pub required: Option<bool>,
+    /// Whether the parameter's type is an `Option<T>`, so that it may also be passed
+    /// as `null`.
+    pub nullable: bool,
+    /// The path of a constant holding the parameter's default value.
+    pub default_const: Option<String>,
+    /// The type given by `#[openrpc(schema = "...")]`, documented instead of
+    /// `schema_ty` and any type override.
+    pub schema: Option<String>,
+    /// The format given by `#[openrpc(format = "...")]`.
+    pub format: Option<String>,
+    /// Whether the parameter is deprecated through `#[openrpc(deprecated)]`.
+    pub deprecated: bool,
+    /// The note given by `#[openrpc(deprecated = "...")]`, if any.
+    pub deprecation_note: Option<String>,
+}
+
+impl ParamInfo {
+    /// Returns the name callers pass the parameter under.
+    pub fn documented_name(&self) -> &str {
+        self.rename.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// An example call of a [`MethodInfo`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExampleInfo {
+    /// The name of the example.
+    pub name: String,
+    /// The example's parameters, as `(parameter name, JSON value)` pairs.
+    pub params: Vec<(String, String)>,
+    /// The example's result, as JSON.
+    pub result: String,
+}
+
+/// An error a [`MethodInfo`] is documented to return.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ErrorInfo {
+    /// The name of the error among the document's error components.
+    pub name: String,
+    /// The JSON-RPC error code.
+    pub code: i64,
+    /// A short description of the error.
+    pub message: String,
+    /// Additional information about the error, as JSON.
+    pub data: Option<String>,
+}
+
+/// Options set through `#[openrpc(...)]` attributes on a method.
+#[derive(Default)]
+struct OpenRpcAttrs {
+    /// Per-parameter options, keyed by parameter name.
+    params: HashMap<String, ParamAttrs>,
+    /// `cfg` predicates the method's documentation is additionally gated on.
+    cfgs: Vec<String>,
+    /// Whether the result may be `null`.
+    result_nullable: bool,
+    /// Hints about the cost of calling the method.
+    cost: Option<Cost>,
+    /// Named examples of the result.
+    result_examples: Vec<(String, String)>,
+    /// Named examples of calls, as `(name, params, result)`, checked once the
+    /// method's parameters are known.
+    examples: Vec<(String, Option<syn::LitStr>, syn::LitStr)>,
+    /// The name of the method replacing this one.
+    replaced_by: Option<String>,
+    /// The errors the method can return.
+    errors: Vec<ErrorInfo>,
+    /// How the method accepts its parameters.
+    param_structure: Option<ParamStructure>,
+    /// The names of the tags the method is grouped under.
+    tags: Vec<String>,
+    /// Whether the method is left out of the documentation.
+    skip: bool,
+    /// The method's summary, if it isn't the first line of its doc comment.
+    summary: Option<String>,
+}
+
+/// Options set for a single parameter, either through
+/// `#[openrpc(param(name = "...", ...))]` on the method or through `#[openrpc(...)]`
+/// on the argument itself.
+#[derive(Default)]
+struct ParamAttrs {
+    rename: Option<String>,
+    default_const: Option<String>,
+    hidden: bool,
+    required: Option<bool>,
+    schema: Option<String>,
+    format: Option<String>,
+    deprecated: bool,
+    deprecation_note: Option<String>,
+}
+
+impl ParamAttrs {
+    /// Parses `#[openrpc(...)]` attributes placed directly on an argument.
+    fn parse_arg(&mut self, attrs: &[syn::Attribute]) -> syn::Result<()> {
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("openrpc")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("hidden_param") {
+                    self.hidden = true;
+                    Ok(())
+                } else {
+                    self.parse_option(&meta)
+                }
+            })?;
+        }
+        for attr in attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("serde") || attr.path().is_ident("argument"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    self.rename = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                } else if meta.input.peek(syn::Token![=]) {
+                    // Other options don't affect the documentation.
+                    meta.value()?.parse::<syn::Expr>()?;
+                } else if meta.input.peek(syn::token::Paren) {
+                    meta.parse_nested_meta(|_| Ok(()))?;
+                }
+                Ok(())
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Parses a single parameter option.
+    fn parse_option(&mut self, meta: &ParseNestedMeta<'_>) -> syn::Result<()> {
+        if meta.path.is_ident("default_const") {
+            self.default_const = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+        } else if meta.path.is_ident("hidden") {
+            self.hidden = true;
+        } else if meta.path.is_ident("required") {
+            self.required = Some(meta.value()?.parse::<syn::LitBool>()?.value);
+        } else if meta.path.is_ident("schema") {
+            let schema = meta.value()?.parse::<syn::LitStr>()?;
+            let ty = schema.parse::<syn::Type>().map_err(|e| {
+                syn::Error::new_spanned(&schema, format!("invalid `schema` type: {e}"))
+            })?;
+            self.schema = Some(ty.to_token_stream().to_string());
+        } else if meta.path.is_ident("format") {
+            self.format = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+        } else if meta.path.is_ident("deprecated") {
+            self.deprecated = true;
+            if meta.input.peek(syn::Token![=]) {
+                self.deprecation_note = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            }
+        } else {
+            return Err(meta.error("unsupported `openrpc` parameter option"));
+        }
+        Ok(())
+    }
+}
+
+impl OpenRpcAttrs {
+    /// Parses all `#[openrpc(...)]` attributes in `attrs`.
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut parsed = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("openrpc")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("param") {
+                    let mut name = None;
+                    let mut param = ParamAttrs::default();
+                    meta.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("name") {
+                            name = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                            Ok(())
+                        } else {
+                            param.parse_option(&meta)
+                        }
+                    })?;
+                    let name = name.ok_or_else(|| meta.error("missing parameter `name`"))?;
+                    parsed.params.insert(name, param);
+                } else if meta.path.is_ident("result") {
+                    meta.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("nullable") {
+                            parsed.result_nullable = true;
+                            Ok(())
+                        } else {
+                            Err(meta.error("unsupported `openrpc` result option"))
+                        }
+                    })?;
+                } else if meta.path.is_ident("cost") {
+                    let mut cost = Cost::default();
+                    meta.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("weight") {
+                            cost.weight = Some(parse_u64(&meta)?);
+                        } else if meta.path.is_ident("timeout_ms") {
+                            cost.timeout_ms = Some(parse_u64(&meta)?);
+                        } else {
+                            return Err(meta.error("unsupported `openrpc` cost option"));
+                        }
+                        Ok(())
+                    })?;
+                    if cost == Cost::default() {
+                        return Err(meta.error("`cost` requires `weight` or `timeout_ms`"));
+                    }
+                    parsed.cost = Some(cost);
+                } else if meta.path.is_ident("result_example") {
+                    let mut name = None;
+                    let mut value = None;
+                    meta.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("name") {
+                            name = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                        } else if meta.path.is_ident("value") {
+                            let lit = meta.value()?.parse::<syn::LitStr>()?;
+                            serde_json::from_str::<serde_json::Value>(&lit.value()).map_err(
+                                |e| syn::Error::new(lit.span(), format!("invalid JSON: {e}")),
+                            )?;
+                            value = Some(lit.value());
+                        } else {
+                            return Err(meta.error("unsupported `openrpc` result example option"));
+                        }
+                        Ok(())
+                    })?;
+                    let name = name.ok_or_else(|| meta.error("missing example `name`"))?;
+                    let value = value.ok_or_else(|| meta.error("missing example `value`"))?;
+                    parsed.result_examples.push((name, value));
+                } else if meta.path.is_ident("example") {
+                    let mut name = None;
+                    let mut params = None;
+                    let mut result = None;
+                    meta.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("name") {
+                            name = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                        } else if meta.path.is_ident("params") {
+                            params = Some(meta.value()?.parse::<syn::LitStr>()?);
+                        } else if meta.path.is_ident("result") {
+                            result = Some(meta.value()?.parse::<syn::LitStr>()?);
+                        } else {
+                            return Err(meta.error("unsupported `openrpc` example option"));
+                        }
+                        Ok(())
+                    })?;
+                    let name = name.ok_or_else(|| meta.error("missing example `name`"))?;
+                    let result = result.ok_or_else(|| {
+                        meta.error(format!("example `{name}` is missing its `result`"))
+                    })?;
+                    parsed.examples.push((name, params, result));
+                } else if meta.path.is_ident("error") {
+                    let mut name = None;
+                    let mut code = None;
+                    let mut message = None;
+                    let mut data = None;
+                    meta.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("name") {
+                            name = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                        } else if meta.path.is_ident("code") {
+                            code = Some(parse_i64(&meta)?);
+                        } else if meta.path.is_ident("message") {
+                            message = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                        } else if meta.path.is_ident("data") {
+                            let lit = meta.value()?.parse::<syn::LitStr>()?;
+                            serde_json::from_str::<serde_json::Value>(&lit.value()).map_err(
+                                |e| syn::Error::new(lit.span(), format!("invalid JSON: {e}")),
+                            )?;
+                            data = Some(lit.value());
+                        } else {
+                            return Err(meta.error("unsupported `openrpc` error option"));
+                        }
+                        Ok(())
+                    })?;
+                    let code = code.ok_or_else(|| meta.error("missing error `code`"))?;
+                    let message = message.ok_or_else(|| meta.error("missing error `message`"))?;
+                    parsed.errors.push(ErrorInfo {
+                        // Unnamed errors are named after their code.
+                        name: name.unwrap_or_else(|| code.to_string()),
+                        code,
+                        message,
+                        data,
+                    });
+                } else if meta.path.is_ident("deprecated_replaced_by") {
+                    parsed.replaced_by = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                } else if meta.path.is_ident("param_structure") {
+                    let lit = meta.value()?.parse::<syn::LitStr>()?;
+                    parsed.param_structure = Some(match lit.value().as_str() {
+                        "by-name" => ParamStructure::ByName,
+                        "by-position" => ParamStructure::ByPosition,
+                        "either" => ParamStructure::Either,
+                        _ => {
+                            return Err(syn::Error::new(
+                                lit.span(),
+                                "expected `by-name`, `by-position` or `either`",
+                            ));
+                        }
+                    });
+                } else if meta.path.is_ident("tag") {
+                    let lit = meta.value()?.parse::<syn::LitStr>()?;
+                    let tag = lit.value();
+                    // Tags are referenced by name from `components.tags`.
+                    if tag.is_empty()
+                        || !tag
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+                    {
+                        return Err(syn::Error::new(
+                            lit.span(),
+                            "tag names may only contain ASCII letters, digits, `.`, `-` and `_`",
+                        ));
+                    }
+                    if !parsed.tags.contains(&tag) {
+                        parsed.tags.push(tag);
+                    }
+                } else if meta.path.is_ident("skip") {
+                    parsed.skip = true;
+                } else if meta.path.is_ident("summary") {
+                    let lit = meta.value()?.parse::<syn::LitStr>()?;
+                    let summary = lit.value().trim().to_owned();
+                    if summary.is_empty() {
+                        return Err(syn::Error::new(lit.span(), "the summary is empty"));
+                    }
+                    parsed.summary = Some(summary);
+                } else if meta.path.is_ident("cfg") {
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    parsed
+                        .cfgs
+                        .push(content.parse::<proc_macro2::TokenStream>()?.to_string());
+                } else {
+                    return Err(meta.error("unsupported `openrpc` option"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(parsed)
+    }
+}
+
+/// Parses the value of a `key = <integer>` option.
+fn parse_u64(meta: &ParseNestedMeta<'_>) -> syn::Result<u64> {
+    let lit = meta.value()?.parse::<syn::LitInt>()?;
+    lit.base10_parse()
+        .map_err(|_| syn::Error::new(lit.span(), "expected a non-negative integer"))
+}
+
+/// Parses the value of a `key = <integer>` option that may be negative.
+fn parse_i64(meta: &ParseNestedMeta<'_>) -> syn::Result<i64> {
+    let input = meta.value()?;
+    let negative = input.parse::<Option<syn::Token![-]>>()?.is_some();
+    let lit = input.parse::<syn::LitInt>()?;
+    let value = if negative {
+        format!("-{}", lit.base10_digits())
+    } else {
+        lit.base10_digits().to_string()
+    };
+    value
+        .parse()
+        .map_err(|_| syn::Error::new(lit.span(), "expected a 64-bit integer"))
+}
+
+/// The maximum number of re-exports followed to find a single trait.
+const MAX_REEXPORT_DEPTH: usize = 8;
+
+/// Parses the given source files and extracts the methods of the requested traits to
+/// document, adding the build warnings to `warnings`.
+pub(crate) fn parse_methods(
+    options: &GenerateOptions,
+    sources: &[Source<'_>],
+    warnings: &mut Vec<String>,
+) -> Result<Vec<MethodInfo>, Box<dyn Error>> {
+    let json_rpc_methods_rs = options.source_name();
+
+    // Parse the source files containing the requested traits.
+    let asts = sources
+        .iter()
+        .map(|(file, source_rs)| match syn::parse_file(source_rs) {
+            Ok(ast) => Ok((*file, ast)),
+            // Keep the error of a single source as it was.
+            Err(e) if sources.len() == 1 => Err(e.into()),
+            Err(e) => Err(format!("{}: {e}", file.display()).into()),
+        })
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+    // Collect all requested traits, along with the file each is defined in.
+    let traits = options
+        .trait_names()
+        .iter()
+        .map(|name| {
+            let (file, ast) = trait_source(&asts, name)?;
+            find_trait(file, ast, name, options.follow_reexports, 0)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let traits = with_supertraits(&asts, traits, warnings);
+    if options.rerun_if_changed {
+        // The source files themselves are reported by `GenerateOptions::generate`.
+        let followed: BTreeSet<_> = traits
+            .iter()
+            .map(|(file, _, _)| file)
+            .filter(|file| !sources.iter().any(|(source, _)| source == file))
+            .collect();
+        for file in followed {
+            emit_rerun_if_changed(file);
+        }
+    }
+
+    // Result aliases given explicitly take precedence over those found in the files.
+    let explicit_aliases = explicit_result_aliases(options)?;
+
+    let mut methods = vec![];
+    for (file, tr, aliases) in &traits {
+        let mut result_aliases = aliases.clone();
+        result_aliases.extend(explicit_aliases.clone());
+
+        methods.extend(
+            trait_methods(tr, aliases, &result_aliases)
+                .map_err(|e| format!("{}: {e}", file.display()))?,
+        );
+    }
+    // Hidden methods are left out before the checks, so that deprecations can't point
+    // at methods that aren't documented.
+    if !options.include_doc_hidden {
+        methods.retain(|method| !method.doc_hidden);
+    }
+    check_methods(&methods).map_err(|e| format!("{json_rpc_methods_rs}: {e}"))?;
+
+    Ok(methods)
+}
+
+/// Adds the supertraits of `traits` defined in the source files, transitively, as their
+/// methods are served along with those of the traits.
+///
+/// Supertraits that aren't defined in the source files are reported in `warnings`,
+/// except for common marker and standard library traits.
+fn with_supertraits(
+    asts: &[(&Path, syn::File)],
+    mut traits: Vec<FoundTrait>,
+    warnings: &mut Vec<String>,
+) -> Vec<FoundTrait> {
+    let mut i = 0;
+    while i < traits.len() {
+        let supertraits: Vec<_> = traits[i]
+            .1
+            .supertraits
+            .iter()
+            .filter_map(|bound| match bound {
+                syn::TypeParamBound::Trait(bound) => bound.path.segments.last(),
+                _ => None,
+            })
+            .map(|segment| segment.ident.to_string())
+            .filter(|name| !STD_SUPERTRAITS.contains(&name.as_str()))
+            .collect();
+        for name in supertraits {
+            if traits.iter().any(|(_, tr, _)| tr.ident == name) {
+                continue;
+            }
+            let found = asts.iter().find_map(|(file, ast)| {
+                ast.items.iter().find_map(|item| match item {
+                    syn::Item::Trait(tr) if tr.ident == name => {
+                        Some((file.to_path_buf(), tr.clone(), type_aliases(ast)))
+                    }
+                    _ => None,
+                })
+            });
+            match found {
+                Some(found) => traits.push(found),
+                None => warnings.push(format!(
+                    "supertrait `{name}` of trait `{}` isn't defined in the source files, so \
+                     its methods aren't documented",
+                    traits[i].1.ident
+                )),
+            }
+        }
+        i += 1;
+    }
+    traits
+}
+
+/// Supertraits that don't contribute methods, and aren't looked for in the sources.
+const STD_SUPERTRAITS: &[&str] = &[
+    "Send", "Sync", "Sized", "Unpin", "Clone", "Copy", "Debug", "Default",
+];
+
+/// Parses the result aliases given through [`GenerateOptions::result_alias`].
+pub(crate) fn explicit_result_aliases(
+    options: &GenerateOptions,
+) -> Result<HashMap<String, syn::Type>, String> {
+    options
+        .result_aliases
+        .iter()
+        .map(|(alias, target)| {
+            let target = syn::parse_str::<syn::Type>(target)
+                .map_err(|e| format!("invalid target `{target}` of result alias `{alias}`: {e}"))?;
+            Ok((alias.clone(), target))
+        })
+        .collect()
+}
+
+/// Extracts the JSON-RPC methods and subscriptions of a single trait.
+///
+/// See [`parse_method`] for `type_aliases` and `result_aliases`.
+pub(crate) fn trait_methods(
+    tr: &syn::ItemTrait,
+    type_aliases: &HashMap<String, syn::Type>,
+    result_aliases: &HashMap<String, syn::Type>,
+) -> syn::Result<Vec<MethodInfo>> {
+    let mut methods = vec![];
+    for item in &tr.items {
+        if let syn::TraitItem::Fn(fn_item) = item
+            && let Some(method) =
+                parse_method(tr, fn_item, type_aliases, result_aliases).map_err(|e| {
+                    syn::Error::new(
+                        e.span(),
+                        format!("invalid method `{}`: {e}", fn_item.sig.ident),
+                    )
+                })?
+        {
+            methods.push(method);
+        }
+    }
+    Ok(methods)
+}
+
+/// Checks that the methods extracted from all traits are consistent with each other.
+pub(crate) fn check_methods(methods: &[MethodInfo]) -> Result<(), String> {
+    // The Rust method each command was declared by, to report duplicates.
+    let mut declared_by = HashMap::new();
+    for method in methods {
+        let rust_name = format!("{}::{}", method.trait_name, method.fn_name);
+        if let Some(other) = declared_by.insert(&method.command, rust_name.clone()) {
+            return Err(format!(
+                "methods `{other}` and `{rust_name}` are both named `{}`",
+                method.command
+            ));
+        }
+    }
+
+    for method in methods {
+        if let Some(replaced_by) = &method.replaced_by
+            && !methods
+                .iter()
+                .any(|m| m.command == *replaced_by || m.aliases.contains(replaced_by))
+        {
+            return Err(format!(
+                "method `{}` is replaced by `{replaced_by}`, which is not a documented method",
+                method.command
+            ));
+        }
+    }
+
+    // Errors are shared through the document's components, so each name can only
+    // stand for a single error.
+    let mut errors = HashMap::new();
+    for method in methods {
+        for error in &method.errors {
+            if let Some((other, other_method)) =
+                errors.insert(&error.name, (error, &method.command))
+                && other != error
+            {
+                return Err(format!(
+                    "methods `{other_method}` and `{}` document different errors named `{}`",
+                    method.command, error.name
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Chooses the source file to look up the trait `name` in: the one defining it, or
+/// failing that the first one re-exporting it.
+///
+/// With a single source file, that file is always chosen, so that [`find_trait`]
+/// reports what is missing.
+fn trait_source<'a>(
+    asts: &'a [(&'a Path, syn::File)],
+    name: &str,
+) -> Result<&'a (&'a Path, syn::File), Box<dyn Error>> {
+    if let [source] = asts {
+        return Ok(source);
+    }
+
+    let mut defining = asts.iter().filter(|(_, ast)| {
+        ast.items
+            .iter()
+            .any(|item| matches!(item, syn::Item::Trait(tr) if tr.ident == name))
+    });
+    if let Some(source) = defining.next() {
+        if let Some((other, _)) = defining.next() {
+            return Err(format!(
+                "trait `{name}` is defined in both {} and {}",
+                source.0.display(),
+                other.display()
+            )
+            .into());
+        }
+        return Ok(source);
+    }
+
+    asts.iter()
+        .find(|(_, ast)| {
+            ast.items.iter().any(|item| match item {
+                syn::Item::Use(item_use) => find_use(&item_use.tree, name, vec![]).is_some(),
+                _ => false,
+            })
+        })
+        .ok_or_else(|| {
+            let files: Vec<_> = asts
+                .iter()
+                .map(|(file, _)| file.display().to_string())
+                .collect();
+            format!(
+                "trait `{name}` must be present in one of {}",
+                files.join(", ")
+            )
+            .into()
+        })
+}
+
+/// A trait found by [`find_trait`].
+type FoundTrait = (PathBuf, syn::ItemTrait, HashMap<String, syn::Type>);
+
+/// Finds the trait `name` in the parsed `file`, following re-exports if requested.
+///
+/// Returns the file the trait is defined in, the trait, and the type aliases defined
+/// next to it.
+fn find_trait(
+    file: &Path,
+    ast: &syn::File,
+    name: &str,
+    follow_reexports: bool,
+    depth: usize,
+) -> Result<FoundTrait, Box<dyn Error>> {
+    if let Some(tr) = ast.items.iter().find_map(|item| match item {
+        syn::Item::Trait(item_trait) if item_trait.ident == name => Some(item_trait),
+        _ => None,
+    }) {
+        return Ok((file.to_path_buf(), tr.clone(), type_aliases(ast)));
+    }
+
+    let Some(target) = ast.items.iter().find_map(|item| match item {
+        syn::Item::Use(item_use) => find_use(&item_use.tree, name, vec![]),
+        _ => None,
+    }) else {
+        let found: Vec<_> = ast
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Trait(item_trait) => Some(format!("`{}`", item_trait.ident)),
+                _ => None,
+            })
+            .collect();
+        let found = if found.is_empty() {
+            "no traits".into()
+        } else {
+            format!("traits {}", found.join(", "))
+        };
+        return Err(format!(
+            "trait `{name}` must be present in {}, which defines {found}",
+            file.display()
+        )
+        .into());
+    };
+
+    if !follow_reexports {
+        return Err(format!(
+            "trait `{name}` is re-exported from `{}` in {} rather than defined there; \
+             enable `GenerateOptions::follow_reexports` to document it",
+            target.join("::"),
+            file.display()
+        )
+        .into());
+    }
+    if depth == MAX_REEXPORT_DEPTH {
+        return Err(format!("too many nested re-exports of trait `{name}`").into());
+    }
+
+    let target_file = resolve_module_file(file, &target[..target.len() - 1]).ok_or_else(|| {
+        format!(
+            "{}: cannot locate the module file for re-export `{}`",
+            file.display(),
+            target.join("::")
+        )
+    })?;
+    let target_rs =
+        fs::read_to_string(&target_file).map_err(|e| format!("{}: {e}", target_file.display()))?;
+    let target_ast =
+        syn::parse_file(&target_rs).map_err(|e| format!("{}: {e}", target_file.display()))?;
+    find_trait(
+        &target_file,
+        &target_ast,
+        &target[target.len() - 1],
+        true,
+        depth + 1,
+    )
+}
+
+/// Collects the non-generic type aliases defined in `ast`, by name.
+fn type_aliases(ast: &syn::File) -> HashMap<String, syn::Type> {
+    ast.items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::Type(alias) if alias.generics.params.is_empty() => {
+                Some((alias.ident.to_string(), (*alias.ty).clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Replaces the `type_aliases` named within `ty` by the types they stand for,
+/// returning whether any was replaced.
+///
+/// Only aliases named by a single identifier are resolved, as a path may name a type
+/// from another module. `expanding` holds the aliases being resolved, which are left
+/// as they are if they are cyclic.
+fn resolve_type_aliases(
+    ty: &mut syn::Type,
+    type_aliases: &HashMap<String, syn::Type>,
+    expanding: &mut Vec<String>,
+) -> bool {
+    match ty {
+        syn::Type::Path(type_path) => {
+            if type_path.qself.is_none()
+                && type_path.path.leading_colon.is_none()
+                && type_path.path.segments.len() == 1
+                && type_path.path.segments[0].arguments.is_none()
+                && let name = type_path.path.segments[0].ident.to_string()
+                && !expanding.contains(&name)
+                && let Some(target) = type_aliases.get(&name)
+            {
+                *ty = target.clone();
+                expanding.push(name);
+                resolve_type_aliases(ty, type_aliases, expanding);
+                expanding.pop();
+                return true;
+            }
+
+            let mut resolved = false;
+            for segment in &mut type_path.path.segments {
+                if let syn::PathArguments::AngleBracketed(args) = &mut segment.arguments {
+                    for arg in &mut args.args {
+                        if let syn::GenericArgument::Type(arg) = arg {
+                            resolved |= resolve_type_aliases(arg, type_aliases, expanding);
+                        }
+                    }
+                }
+            }
+            resolved
+        }
+        syn::Type::Tuple(tuple) => tuple.elems.iter_mut().fold(false, |resolved, elem| {
+            resolve_type_aliases(elem, type_aliases, expanding) | resolved
+        }),
+        syn::Type::Array(syn::TypeArray { elem, .. })
+        | syn::Type::Slice(syn::TypeSlice { elem, .. })
+        | syn::Type::Reference(syn::TypeReference { elem, .. })
+        | syn::Type::Paren(syn::TypeParen { elem, .. })
+        | syn::Type::Group(syn::TypeGroup { elem, .. }) => {
+            resolve_type_aliases(elem, type_aliases, expanding)
+        }
+        _ => false,
+    }
+}
+
+/// Replaces the references within `ty` by the types they borrow, dropping their
+/// lifetimes, with `&str` owned as `String` and `&[T]` as `Vec<T>`.
+fn own_references(ty: &mut syn::Type) {
+    match ty {
+        syn::Type::Reference(reference) => {
+            let mut elem = reference.elem.as_ref().clone();
+            own_references(&mut elem);
+            *ty = match elem {
+                syn::Type::Path(type_path) if type_path.path.is_ident("str") => {
+                    syn::parse_quote!(String)
+                }
+                syn::Type::Slice(slice) => {
+                    let elem = slice.elem;
+                    syn::parse_quote!(Vec<#elem>)
+                }
+                elem => elem,
+            };
+        }
+        syn::Type::Path(type_path) => {
+            for segment in &mut type_path.path.segments {
+                if let syn::PathArguments::AngleBracketed(args) = &mut segment.arguments {
+                    for arg in &mut args.args {
+                        if let syn::GenericArgument::Type(arg) = arg {
+                            own_references(arg);
+                        }
+                    }
+                }
+            }
+        }
+        syn::Type::Tuple(tuple) => tuple.elems.iter_mut().for_each(own_references),
+        syn::Type::Array(syn::TypeArray { elem, .. })
+        | syn::Type::Slice(syn::TypeSlice { elem, .. })
+        | syn::Type::Paren(syn::TypeParen { elem, .. })
+        | syn::Type::Group(syn::TypeGroup { elem, .. }) => own_references(elem),
+        _ => {}
+    }
+}
+
+/// Returns the value of the constant `name` in the inline module `module` of `ast`, or
+/// at its top level without a module, if it is defined as a string literal.
+pub(crate) fn module_str_const(
+    ast: &syn::File,
+    module: Option<&str>,
+    name: &str,
+) -> Option<String> {
+    match module_const(ast, module, name)?.expr.as_ref() {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(description),
+            ..
+        }) => Some(description.value()),
+        _ => None,
+    }
+}
+
+/// Finds the constant `name` defined in the inline module `module` of `ast`, or at its
+/// top level if `module` is `None`.
+fn module_const<'a>(
+    ast: &'a syn::File,
+    module: Option<&str>,
+    name: &str,
+) -> Option<&'a syn::ItemConst> {
+    module_items(ast, module)?
+        .iter()
+        .find_map(|item| match item {
+            syn::Item::Const(item_const) if item_const.ident == name => Some(item_const),
+            _ => None,
+        })
+}
+
+/// Returns whether the module `module` declared in `ast` may define the constant
+/// `name`: it does if the module is inline, and may if it is in a file of its own.
+pub(crate) fn may_define_const(ast: &syn::File, module: &str, name: &str) -> bool {
+    ast.items.iter().any(|item| match item {
+        syn::Item::Mod(item_mod) if item_mod.ident == module => match &item_mod.content {
+            Some(_) => module_const(ast, Some(module), name).is_some(),
+            None => true,
+        },
+        _ => false,
+    })
+}
+
+/// Returns whether `ast` defines the constant `name` at its top level.
+pub(crate) fn defines_const(ast: &syn::File, name: &str) -> bool {
+    module_const(ast, None, name).is_some()
+}
+
+/// Returns the items of the inline module `module` of `ast`, or its top-level items if
+/// `module` is `None`.
+fn module_items<'a>(ast: &'a syn::File, module: Option<&str>) -> Option<&'a [syn::Item]> {
+    match module {
+        Some(module) => ast.items.iter().find_map(|item| match item {
+            syn::Item::Mod(item_mod) if item_mod.ident == module => {
+                item_mod.content.as_ref().map(|(_, items)| items.as_slice())
+            }
+            _ => None,
+        }),
+        None => Some(&ast.items),
+    }
+}
+
+/// Returns the full path a `use` tree imports as `name`, if any.
+fn find_use(tree: &syn::UseTree, name: &str, mut prefix: Vec<String>) -> Option<Vec<String>> {
+    match tree {
+        syn::UseTree::Path(path) => {
+            prefix.push(path.ident.to_string());
+            find_use(&path.tree, name, prefix)
+        }
+        syn::UseTree::Name(use_name) if use_name.ident == name => {
+            prefix.push(name.to_string());
+            Some(prefix)
+        }
+        syn::UseTree::Rename(rename) if rename.rename == name => {
+            prefix.push(rename.ident.to_string());
+            Some(prefix)
+        }
+        syn::UseTree::Group(group) => group
+            .items
+            .iter()
+            .find_map(|tree| find_use(tree, name, prefix.clone())),
+        _ => None,
+    }
+}
+
+/// Locates the file of the module at `module_path`, relative to the module defined in
+/// `file`.
+fn resolve_module_file(file: &Path, module_path: &[String]) -> Option<PathBuf> {
+    // The directory containing the files of the current module's submodules.
+    let parent = file.parent()?;
+    let mut dir = match file.file_stem()?.to_str()? {
+        "mod" | "lib" | "main" => parent.to_path_buf(),
+        stem => parent.join(stem),
+    };
+    let mut module_file = Some(file.to_path_buf());
+
+    for segment in module_path {
+        match segment.as_str() {
+            "self" => {}
+            "super" => {
+                dir = dir.parent()?.to_path_buf();
+                module_file = None;
+            }
+            module => {
+                let candidates = [
+                    dir.join(format!("{module}.rs")),
+                    dir.join(module).join("mod.rs"),
+                ];
+                module_file = Some(candidates.into_iter().find(|path| path.is_file())?);
+                dir = dir.join(module);
+            }
+        }
+    }
+
+    module_file
+}
+
+/// Extracts a single method, if it is a JSON-RPC method or subscription.
+///
+/// `type_aliases` are the type aliases defined in the trait's file, which are resolved
+/// in the types documented by their schema. `result_aliases` maps type aliases (as
+/// written, ignoring whitespace) to the types they stand for, so that results returned
+/// through an alias of `RpcResult<T>` are documented as `T`.
+fn parse_method(
+    tr: &syn::ItemTrait,
+    method: &syn::TraitItemFn,
+    type_aliases: &HashMap<String, syn::Type>,
+    result_aliases: &HashMap<String, syn::Type>,
+) -> syn::Result<Option<MethodInfo>> {
+    // Find methods via their `#[method(name = "command")]` attribute, and
+    // subscriptions via their `#[subscription(name = "command", item = T)]`
+    // attribute.
+    let mut rpc_attrs = method
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("method") || attr.path().is_ident("subscription"));
+    let Some(rpc_attr) = rpc_attrs.next() else {
+        return Ok(None);
+    };
+    if let Some(extra) = rpc_attrs.next() {
+        return Err(syn::Error::new_spanned(
+            extra,
+            "multiple `method` or `subscription` attributes",
+        ));
+    }
+
+    let mut command = None;
+    let mut aliases = vec![];
+    let mut item = None;
+    let mut unsubscribe = None;
+    // Options this doesn't know how to skip are tolerated as long as the name is found.
+    let parsed = rpc_attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("name") {
+            command = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            // Skip a custom notification name (`"sub" => "notif"`).
+            if meta.input.peek(syn::Token![=>]) {
+                meta.input.parse::<syn::Token![=>]>()?;
+                meta.input.parse::<syn::LitStr>()?;
+            }
+        } else if meta.path.is_ident("item") {
+            item = Some(meta.value()?.parse::<syn::Type>()?);
+        } else if meta.path.is_ident("unsubscribe") {
+            unsubscribe = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+        } else if meta.path.is_ident("aliases") {
+            let list = meta.value()?.parse::<syn::ExprArray>()?;
+            for elem in list.elems {
+                if let syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(alias),
+                    ..
+                }) = elem
+                {
+                    aliases.push(alias.value());
+                }
+            }
+        } else if meta.input.peek(syn::Token![=]) {
+            // Skip other `key = value` options (e.g. `param_kind`).
+            meta.value()?.parse::<syn::Expr>()?;
+        }
+        Ok(())
+    });
+
+    // `jsonrpsee` requires a name, so don't leave such a method out silently.
+    let Some(command) = command else {
+        let kind = if rpc_attr.path().is_ident("method") {
+            "method"
+        } else {
+            "subscription"
+        };
+        let message = match parsed {
+            Ok(()) => format!("`{kind}` attribute without a `name`"),
+            Err(e) => format!("`{kind}` attribute without a readable `name`: {e}"),
+        };
+        return Err(syn::Error::new_spanned(rpc_attr, message));
+    };
+
+    let mut openrpc_attrs = OpenRpcAttrs::parse(&method.attrs)?;
+    if openrpc_attrs.skip {
+        return Ok(None);
+    }
+
+    // An `Option<T>` item is documented as a possibly `null` `T`, as results are.
+    let (item, item_optional) = match item {
+        Some(item) => match generic_wrapper(&item, |ident| ident == "Option") {
+            Some(inner) => (Some(inner.clone()), true),
+            None => (Some(item), false),
+        },
+        None => (None, false),
+    };
+
+    // Subscriptions may leave out their return type, as it isn't their result.
+    let unit = syn::parse_quote!(());
+    let result_ty = match &method.sig.output {
+        syn::ReturnType::Type(_, ret) => ret.as_ref(),
+        syn::ReturnType::Default if item.is_some() => &unit,
+        syn::ReturnType::Default => {
+            return Err(syn::Error::new_spanned(
+                &method.sig,
+                "documented methods must declare a return type",
+            ));
+        }
+    };
+
+    // The result of a subscription (e.g. `SubscriptionResult`) only reports whether
+    // it was set up; its `item` is documented instead.
+    let payload = match item {
+        Some(_) => None,
+        None => result_payload(
+            result_aliases
+                .get(&normalize_type(&result_ty.to_token_stream().to_string()))
+                .unwrap_or(result_ty),
+        ),
+    };
+
+    let mut params = vec![];
+    for arg in &method.sig.inputs {
+        if let syn::FnArg::Typed(pat_type) = arg
+            && let syn::Pat::Ident(pat_ident) = pat_type.pat.as_ref()
+            // The request's extensions are passed by the server, not by callers.
+            && !is_extensions(&pat_type.ty)
+        {
+            let name = pat_ident.ident.to_string();
+            let mut param_attrs = openrpc_attrs.params.remove(&name).unwrap_or_default();
+            param_attrs.parse_arg(&pat_type.attrs)?;
+
+            // Hidden parameters are populated by the server, not by callers.
+            if param_attrs.hidden {
+                continue;
+            }
+
+            params.push(parse_param(
+                name,
+                pat_type.ty.as_ref(),
+                type_aliases,
+                param_attrs,
+            )?);
+        }
+    }
+
+    // The per-parameter constants live in the module the result type is named from,
+    // or next to the description constants for maps, tuples and arrays, which don't
+    // name one.
+    let module = match payload.map_or(result_ty, |(payload, _)| payload) {
+        ty if is_map(ty) || matches!(ty, syn::Type::Tuple(_) | syn::Type::Array(_)) => {
+            Some(String::new())
+        }
+        syn::Type::Path(type_path) => type_path
+            .path
+            .segments
+            .first()
+            .map(|segment| segment.ident.to_string()),
+        _ => None,
+    };
+    let module = match module {
+        Some(module) => module,
+        None if params.is_empty() => String::new(),
+        None => {
+            return Err(syn::Error::new_spanned(
+                result_ty,
+                "the return type of a method with parameters must be a path, naming the module of their constants",
+            ));
+        }
+    };
+
+    if let Some(name) = openrpc_attrs.params.keys().next() {
+        return Err(syn::Error::new_spanned(
+            &method.sig,
+            format!("`openrpc` options refer to unknown parameter `{name}`"),
+        ));
+    }
+
+    let examples = openrpc_attrs
+        .examples
+        .into_iter()
+        .map(|(name, example_params, result)| parse_example(name, example_params, result, &params))
+        .collect::<syn::Result<_>>()?;
+
+    // Gate the method on its own `#[cfg]` attributes, as well as on those its
+    // documentation was annotated with (e.g. for feature-gated result types).
+    let mut cfgs = method
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"))
+        .map(|attr| Ok(attr.meta.require_list()?.tokens.to_string()))
+        .collect::<syn::Result<Vec<_>>>()?;
+    cfgs.append(&mut openrpc_attrs.cfgs);
+    let cfg = match cfgs.len() {
+        0 => None,
+        1 => cfgs.pop(),
+        _ => Some(format!("all({})", cfgs.join(", "))),
+    };
+
+    let doc_lines = method
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| {
+            if let syn::Meta::NameValue(doc_line) = &attr.meta
+                && let syn::Expr::Lit(docs) = &doc_line.value
+                && let syn::Lit::Str(s) = &docs.lit
+            {
+                // Trim the space following `///`, keeping any further indentation.
+                let line = s.value();
+                Some(line.strip_prefix(' ').unwrap_or(&line).to_string())
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    let deprecated = method
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("deprecated"))
+        .map(deprecation_note)
+        .transpose()?;
+
+    // The module is named from the result type as written, while its schema uses the
+    // types any local aliases stand for.
+    let schema_result_ty = item
+        .as_ref()
+        .or(payload.map(|(payload, _)| payload))
+        .map(|ty| {
+            let mut schema_ty = ty.clone();
+            let aliased = resolve_type_aliases(&mut schema_ty, type_aliases, &mut vec![]);
+            (schema_ty, aliased.then(|| ty.to_token_stream().to_string()))
+        });
+    let (schema_result_ty, aliased_result_ty) = match schema_result_ty {
+        Some((ty, aliased)) => (Some(ty.to_token_stream().to_string()), aliased),
+        None => (None, None),
+    };
+
+    Ok(Some(MethodInfo {
+        command,
+        aliases,
+        doc_lines,
+        summary: openrpc_attrs.summary,
+        module,
+        params,
+        result_ty: result_ty.to_token_stream().to_string(),
+        result_schema_ty: schema_result_ty.clone().filter(|_| item.is_none()),
+        result_nullable: openrpc_attrs.result_nullable
+            || item_optional
+            || payload.is_some_and(|(_, optional)| optional),
+        item: schema_result_ty.filter(|_| item.is_some()),
+        aliased_result_ty,
+        unsubscribe,
+        // Naming a replacement implies that the method is deprecated.
+        deprecated: openrpc_attrs.replaced_by.is_some() || deprecated.is_some(),
+        replaced_by: openrpc_attrs.replaced_by,
+        deprecation_note: deprecated.flatten(),
+        doc_hidden: method.attrs.iter().any(is_doc_hidden),
+        trait_name: tr.ident.to_string(),
+        fn_name: method.sig.ident.to_string(),
+        cost: openrpc_attrs.cost,
+        result_examples: openrpc_attrs.result_examples,
+        examples,
+        errors: openrpc_attrs.errors,
+        param_structure: openrpc_attrs.param_structure.unwrap_or_default(),
+        tags: openrpc_attrs.tags,
+        cfg,
+    }))
+}
+
+/// Checks the JSON of an example call, pairing its parameters with those of the method.
+///
+/// Parameters are given as a JSON array, by position, or as a JSON object, by name.
+fn parse_example(
+    name: String,
+    example_params: Option<syn::LitStr>,
+    result: syn::LitStr,
+    params: &[ParamInfo],
+) -> syn::Result<ExampleInfo> {
+    let error = |lit: &syn::LitStr, message: String| {
+        syn::Error::new(lit.span(), format!("example `{name}`: {message}"))
+    };
+    let json = |lit: &syn::LitStr, what: &str| {
+        serde_json::from_str::<serde_json::Value>(&lit.value())
+            .map_err(|e| error(lit, format!("invalid JSON {what}: {e}")))
+    };
+
+    json(&result, "result")?;
+    let example_params = match &example_params {
+        None => vec![],
+        Some(lit) => match json(lit, "params")? {
+            serde_json::Value::Array(values) => {
+                if values.len() > params.len() {
+                    return Err(error(
+                        lit,
+                        format!(
+                            "{} parameters given, but the method takes {}",
+                            values.len(),
+                            params.len()
+                        ),
+                    ));
+                }
+                params
+                    .iter()
+                    .zip(values)
+                    .map(|(param, value)| (param.documented_name().to_string(), value.to_string()))
+                    .collect()
+            }
+            serde_json::Value::Object(values) => {
+                let mut example_params = vec![];
+                // Follow the order of the method's parameters, as callers would.
+                for param in params {
+                    if let Some(value) = values.get(param.documented_name()) {
+                        example_params
+                            .push((param.documented_name().to_string(), value.to_string()));
+                    }
+                }
+                if let Some(unknown) = values
+                    .keys()
+                    .find(|key| !params.iter().any(|p| p.documented_name() == *key))
+                {
+                    return Err(error(lit, format!("unknown parameter `{unknown}`")));
+                }
+                example_params
+            }
+            _ => {
+                return Err(error(
+                    lit,
+                    "params must be a JSON array or object".to_string(),
+                ));
+            }
+        },
+    };
+
+    Ok(ExampleInfo {
+        name,
+        params: example_params,
+        result: result.value(),
+    })
+}
+
+/// Returns the success type of a result type such as `RpcResult<T>`, `Result<T, E>`, or
+/// `ResponsePayload<'a, T>`, and whether it is optional.
+///
+/// Other generic types, such as `ListResult<T>`, are payloads of their own, so aliases
+/// of the wrappers are resolved beforehand. An `Option<T>` within the wrapper is
+/// unwrapped to `T` as well.
+fn result_payload(ty: &syn::Type) -> Option<(&syn::Type, bool)> {
+    let payload = generic_wrapper(ty, |ident| {
+        matches!(ident, "Result" | "RpcResult" | "ResponsePayload")
+    })?;
+    Some(match generic_wrapper(payload, |ident| ident == "Option") {
+        Some(inner) => (inner, true),
+        None => (payload, false),
+    })
+}
+
+/// Map types, documented as objects whose values share a schema.
+const MAP_TYPES: &[&str] = &["HashMap", "BTreeMap", "IndexMap"];
+
+/// Returns whether `ty` is a map, such as `HashMap<K, V>` or
+/// `std::collections::BTreeMap<K, V>`.
+fn is_map(ty: &syn::Type) -> bool {
+    generic_wrapper(ty, |ident| MAP_TYPES.contains(&ident)).is_some()
+}
+
+/// Returns the first type argument of `ty`, if its last path segment's name is
+/// accepted by `is_wrapper`.
+fn generic_wrapper(ty: &syn::Type, is_wrapper: impl Fn(&str) -> bool) -> Option<&syn::Type> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if !is_wrapper(&segment.ident.to_string()) {
+        return None;
+    }
+    // Skip lifetimes, such as that of `ResponsePayload`.
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+/// Returns the note given by a `#[deprecated]` attribute, if any.
+fn deprecation_note(attr: &syn::Attribute) -> syn::Result<Option<String>> {
+    match &attr.meta {
+        syn::Meta::Path(_) => Ok(None),
+        // `#[deprecated = "note"]`
+        syn::Meta::NameValue(meta) => match &meta.value {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(note),
+                ..
+            }) => Ok(Some(note.value())),
+            value => Err(syn::Error::new_spanned(value, "expected a string")),
+        },
+        // `#[deprecated(since = "...", note = "...")]`
+        syn::Meta::List(_) => {
+            let mut note = None;
+            attr.parse_nested_meta(|meta| {
+                let value = meta.value()?.parse::<syn::LitStr>()?;
+                if meta.path.is_ident("note") {
+                    note = Some(value.value());
+                }
+                Ok(())
+            })?;
+            Ok(note)
+        }
+    }
+}
+
+/// Returns whether `ty` is a reference to `jsonrpsee`'s `Extensions`.
+fn is_extensions(ty: &syn::Type) -> bool {
+    if let syn::Type::Reference(reference) = ty
+        && let syn::Type::Path(type_path) = reference.elem.as_ref()
+        && let Some(last) = type_path.path.segments.last()
+    {
+        last.ident == "Extensions"
+    } else {
+        false
+    }
+}
+
+/// Returns whether `attr` is `#[doc(hidden)]`.
+fn is_doc_hidden(attr: &syn::Attribute) -> bool {
+    if !attr.path().is_ident("doc") || !matches!(attr.meta, syn::Meta::List(_)) {
+        return false;
+    }
+    let mut hidden = false;
+    let _ = attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("hidden") {
+            hidden = true;
+        } else if meta.input.peek(syn::Token![=]) {
+            meta.value()?.parse::<syn::Expr>()?;
+        }
+        Ok(())
+    });
+    hidden
+}
+
+/// Extracts a single JSON-RPC parameter.
+fn parse_param(
+    name: String,
+    written_ty: &syn::Type,
+    type_aliases: &HashMap<String, syn::Type>,
+    param_attrs: ParamAttrs,
+) -> syn::Result<ParamInfo> {
+    // Resolve aliases first, so that an alias of `Option<T>` is still optional.
+    let mut resolved_ty = written_ty.clone();
+    let aliased = resolve_type_aliases(&mut resolved_ty, type_aliases, &mut vec![]);
+    // Borrowed arguments are documented as the owned types they deserialize from, so
+    // that neither their lifetimes nor unsized types end up in the generated code.
+    own_references(&mut resolved_ty);
+    let rust_ty = &resolved_ty;
+
+    // Pointer wrappers are transparent to callers, so look through them for an `Option`.
+    let mut unwrapped_ty = rust_ty;
+    while let Some(inner) =
+        generic_wrapper(unwrapped_ty, |ident| matches!(ident, "Box" | "Arc" | "Rc"))
+    {
+        unwrapped_ty = inner;
+    }
+
+    // If we can determine the parameter's optionality, do so.
+    let (param_ty, required, nullable) = match unwrapped_ty {
+        syn::Type::Path(type_path) => {
+            let is_standalone_ident =
+                type_path.path.leading_colon.is_none() && type_path.path.segments.len() == 1;
+            let first_segment = &type_path.path.segments[0];
+
+            if first_segment.ident == "Option" && is_standalone_ident {
+                // Strip the `Option<_>` for the schema type.
+                let schema_ty = match &first_segment.arguments {
+                    syn::PathArguments::AngleBracketed(args) => match args.args.first() {
+                        Some(syn::GenericArgument::Type(ty)) => Some(ty),
+                        _ => None,
+                    },
+                    _ => None,
+                }
+                .ok_or_else(|| {
+                    syn::Error::new_spanned(
+                        unwrapped_ty,
+                        format!("parameter `{name}` has an `Option` type without a type argument"),
+                    )
+                })?;
+                (schema_ty, Some(false), true)
+            } else if first_segment.ident == "Vec" {
+                // We don't know whether the vec may be empty.
+                (rust_ty, None, false)
+            } else {
+                (rust_ty, Some(true), false)
+            }
+        }
+        _ => (rust_ty, Some(true), false),
+    };
+
+    Ok(ParamInfo {
+        name,
+        rename: param_attrs.rename,
+        schema_ty: param_ty.to_token_stream().to_string(),
+        aliased_ty: aliased.then(|| written_ty.to_token_stream().to_string()),
+        // An explicit `required` states the author's intent, whatever the type.
+        required: param_attrs.required.or(required),
+        nullable,
+        default_const: param_attrs.default_const,
+        schema: param_attrs.schema,
+        format: param_attrs.format,
+        deprecated: param_attrs.deprecated,
+        deprecation_note: param_attrs.deprecation_note,
+    })
+}
@@ -0,0 +1,1152 @@
+use std::{fs, path::PathBuf};
+
+use crate::{DocCheck, GenerateOptions, SchemaOverride};
+
+/// A trait with a single method, named `getblockcount`.
+const BLOCK_COUNT_RS: &str = r#"
+pub trait Rpc {
+    /// Returns the block count.
+    #[method(name = "getblockcount")]
+    fn get_block_count(&self) -> RpcResult<u64>;
+}
+"#;
+
+/// Generates the lookup table of the `Rpc` trait in `source`, with default options.
+fn generate(source: &str) -> String {
+    crate::generate_rpc_openrpc_from_str(source, &["Rpc"]).unwrap()
+}
+
+/// Returns an empty directory for the test `name` to write to.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("openrpsee-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn cache_skips_unchanged_inputs() {
+    let dir = test_dir("cache-unchanged");
+    let source = dir.join("methods.rs");
+    fs::write(&source, BLOCK_COUNT_RS).unwrap();
+    let output = dir.join("rpc_openrpc.rs");
+    let options = GenerateOptions::new(&source, &["Rpc"], &dir)
+        .rerun_if_changed(false)
+        .cache(true);
+
+    options.generate().unwrap();
+    assert!(
+        fs::read_to_string(&output)
+            .unwrap()
+            .contains("getblockcount")
+    );
+    assert!(dir.join("rpc_openrpc.fingerprint").exists());
+
+    // Nothing is written while the inputs are unchanged.
+    fs::write(&output, "stale").unwrap();
+    options.generate().unwrap();
+    assert_eq!(fs::read_to_string(&output).unwrap(), "stale");
+
+    // Changing the source regenerates the file.
+    fs::write(
+        &source,
+        BLOCK_COUNT_RS.replace("getblockcount", "blockcount"),
+    )
+    .unwrap();
+    options.generate().unwrap();
+    assert!(
+        fs::read_to_string(&output)
+            .unwrap()
+            .contains("\"blockcount\"")
+    );
+}
+
+#[test]
+fn cache_is_invalidated_by_options() {
+    let dir = test_dir("cache-options");
+    let source = dir.join("methods.rs");
+    fs::write(&source, BLOCK_COUNT_RS).unwrap();
+    let output = dir.join("rpc_openrpc.rs");
+    let options = GenerateOptions::new(&source, &["Rpc"], &dir)
+        .rerun_if_changed(false)
+        .cache(true);
+
+    options.generate().unwrap();
+    fs::write(&output, "stale").unwrap();
+    options
+        .clone()
+        .result_name_suffix("_reply")
+        .generate()
+        .unwrap();
+    assert!(
+        fs::read_to_string(&output)
+            .unwrap()
+            .contains("getblockcount_reply")
+    );
+}
+
+#[test]
+fn subscriptions_document_their_item() {
+    let generated = generate(
+        r#"
+pub trait Rpc {
+    /// Subscribes to new heights.
+    #[subscription(name = "subscribeHeights" => "height", unsubscribe = "unsubscribeHeights", item = u64)]
+    async fn heights(&self) -> SubscriptionResult;
+}
+"#,
+    );
+    assert!(generated.contains("    subscription: true,\n"));
+    assert!(generated.contains("    unsubscribe: Some(\"unsubscribeHeights\"),\n"));
+    assert!(generated.contains("    result: |g| g.item::<u64>(\"subscribeHeights_item\"),\n"));
+}
+
+#[test]
+fn optional_subscription_items_are_nullable() {
+    let generated = generate(
+        r#"
+pub trait Rpc {
+    /// Subscribes to the best block hash, `null` while syncing.
+    #[subscription(name = "subscribeBestHash" => "bestHash", unsubscribe = "unsubscribeBestHash", item = Option<String>)]
+    async fn best_hash(&self) -> SubscriptionResult;
+}
+"#,
+    );
+    assert!(generated.contains("    subscription: true,\n"));
+    assert!(generated.contains("    unsubscribe: Some(\"unsubscribeBestHash\"),\n"));
+    assert!(
+        generated
+            .contains("    result: |g| g.item::<String>(\"subscribeBestHash_item\").nullable(),\n")
+    );
+}
+
+#[test]
+fn methods_are_tagged_with_their_trait() {
+    let generated = crate::generate_rpc_openrpc_from_str(
+        r#"
+pub struct Balance {
+    pub amount: u64,
+}
+
+pub trait Wallet {
+    /// Returns the balance.
+    #[method(name = "getbalance")]
+    fn get_balance(&self) -> RpcResult<Balance>;
+}
+
+pub trait Network {
+    /// Returns a peer's balance.
+    #[method(name = "getpeerbalance")]
+    fn get_peer_balance(&self) -> RpcResult<Balance>;
+}
+"#,
+        &["Wallet", "Network"],
+    )
+    .unwrap();
+    let (wallet, network) = generated.split_once("\"getpeerbalance\" =>").unwrap();
+    assert!(wallet.contains("    tags: &[\"Wallet\", ],\n"));
+    assert!(wallet.contains("    result: |g| g.result_of::<Balance>(\"getbalance_result\"),\n"));
+    assert!(network.contains("    tags: &[\"Network\", ],\n"));
+    assert!(
+        network.contains("    result: |g| g.result_of::<Balance>(\"getpeerbalance_result\"),\n")
+    );
+}
+
+#[test]
+fn cache_records_warnings() {
+    let dir = test_dir("cache-warnings");
+    let source = dir.join("methods.rs");
+    fs::write(
+        &source,
+        BLOCK_COUNT_RS.replace("#[method", "#[deprecated = \"Use getinfo.\"]\n    #[method"),
+    )
+    .unwrap();
+    GenerateOptions::new(&source, &["Rpc"], &dir)
+        .rerun_if_changed(false)
+        .cache(true)
+        .warn_deprecated(true)
+        .generate()
+        .unwrap();
+
+    // The warnings follow the fingerprint, to be emitted again on a cache hit.
+    let fingerprint = fs::read_to_string(dir.join("rpc_openrpc.fingerprint")).unwrap();
+    let warnings: Vec<_> = fingerprint.lines().skip(1).collect();
+    assert_eq!(
+        warnings,
+        ["method 'getblockcount' is deprecated: Use getinfo."]
+    );
+}
+
+#[test]
+fn feature_gated_results_are_guarded() {
+    let generated = generate(
+        r#"
+pub trait Rpc {
+    /// Returns the mempool.
+    #[method(name = "getmempool")]
+    #[openrpc(cfg(feature = "mempool"))]
+    fn get_mempool(&self) -> RpcResult<Mempool>;
+}
+"#,
+    );
+    assert!(generated.contains("    available: cfg!(feature = \"mempool\"),\n"));
+    assert!(generated.contains(
+        "        #[cfg(feature = \"mempool\")]
+        let result: fn(&mut openrpsee::openrpc::Generator) -> openrpsee::openrpc::ContentDescriptor = |g| g.result_of::<Mempool>(\"getmempool_result\");
+        #[cfg(not(feature = \"mempool\"))]
+        let result: fn(&mut openrpsee::openrpc::Generator) -> openrpsee::openrpc::ContentDescriptor = |g| g.item::<()>(\"unavailable\");
+"
+    ));
+    // `Mempool` is only referenced under the guard.
+    assert_eq!(generated.matches("Mempool").count(), 1);
+}
+
+#[test]
+fn byte_newtypes_are_documented_as_strings() {
+    let generated = GenerateOptions::new("methods.rs", &["Rpc"], &std::env::temp_dir())
+        .type_override("Hash", SchemaOverride::hex_bytes())
+        .generate_from_str(
+            r#"
+pub struct Hash([u8; 32]);
+
+pub trait Rpc {
+    /// Returns the hash of a block.
+    #[method(name = "getblockhash")]
+    fn get_block_hash(&self, parent: Hash) -> RpcResult<Hash>;
+}
+"#,
+        )
+        .unwrap();
+    assert!(generated.contains(
+        "        _g.param::<String>(\"parent\", crate::methods::PARAM_PARENT_DESC, true).with_format(\"hex\"),\n"
+    ));
+    assert!(generated.contains(
+        "    result: |g| g.result_of::<String>(\"getblockhash_result\").with_format(\"hex\"),\n"
+    ));
+}
+
+#[test]
+fn hidden_params_are_left_out() {
+    let generated = generate(
+        r#"
+pub trait Rpc {
+    /// Sends coins.
+    #[method(name = "send")]
+    #[openrpc(param(name = "origin", hidden))]
+    fn send(
+        &self,
+        to: String,
+        #[openrpc(hidden_param)] peer: PeerInfo,
+        amount: u64,
+        origin: Origin,
+    ) -> RpcResult<()>;
+}
+"#,
+    );
+    assert!(generated.contains(
+        "    params: |_g| vec![
+        _g.param::<String>(\"to\", crate::methods::PARAM_TO_DESC, true),
+        _g.param::<u64>(\"amount\", crate::methods::PARAM_AMOUNT_DESC, true),
+    ],
+"
+    ));
+    assert!(!generated.contains("PeerInfo"));
+    assert!(!generated.contains("Origin"));
+}
+
+#[test]
+fn functions_without_method_attributes_are_skipped() {
+    let generated = generate(&BLOCK_COUNT_RS.replace(
+        "}\n",
+        "
+    /// A helper that isn't exposed over JSON-RPC.
+    fn helper(&self) -> u64;
+}
+",
+    ));
+    assert_eq!(
+        generated
+            .matches("=> openrpsee::openrpc::RpcMethod {")
+            .count(),
+        1
+    );
+    assert!(!generated.contains("helper"));
+}
+
+#[test]
+fn multiple_method_attributes_are_rejected() {
+    let error = crate::generate_rpc_openrpc_from_str(
+        &BLOCK_COUNT_RS.replace("#[method", "#[method(name = \"blockcount\")]\n    #[method"),
+        &["Rpc"],
+    )
+    .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "<source>: invalid method `get_block_count`: multiple `method` or `subscription` attributes"
+    );
+}
+
+#[test]
+fn results_can_be_marked_nullable() {
+    let generated =
+        generate(&BLOCK_COUNT_RS.replace("#[method", "#[openrpc(result(nullable))]\n    #[method"));
+    assert!(
+        generated
+            .contains("    result: |g| g.result_of::<u64>(\"getblockcount_result\").nullable(),\n")
+    );
+}
+
+#[test]
+fn methods_without_params_have_empty_params() {
+    let generated = generate(BLOCK_COUNT_RS);
+    assert!(generated.contains("    params: |_g| vec![],\n"));
+    assert!(!generated.contains("PARAM_"));
+}
+
+/// A trait with a method marked `#[doc(hidden)]`, along with a documented one.
+const DOC_HIDDEN_RS: &str = r#"
+pub trait Rpc {
+    /// Returns the block count.
+    #[method(name = "getblockcount")]
+    fn get_block_count(&self) -> RpcResult<u64>;
+
+    /// Sets the time the node believes it is.
+    #[doc(hidden)]
+    #[method(name = "setmocktime")]
+    fn set_mock_time(&self, timestamp: u64) -> RpcResult<()>;
+}
+"#;
+
+#[test]
+fn doc_hidden_methods_are_left_out_unless_included() {
+    let generated = generate(DOC_HIDDEN_RS);
+    assert!(generated.contains("\"getblockcount\" =>"));
+    assert!(!generated.contains("setmocktime"));
+
+    let generated = GenerateOptions::new("methods.rs", &["Rpc"], &std::env::temp_dir())
+        .include_doc_hidden(true)
+        .generate_from_str(DOC_HIDDEN_RS)
+        .unwrap();
+    assert!(generated.contains("\"getblockcount\" =>"));
+    assert!(generated.contains("\"setmocktime\" =>"));
+}
+
+#[test]
+fn replacements_must_be_documented() {
+    let source = r#"
+pub trait Rpc {
+    /// Returns information about the wallet.
+    #[deprecated]
+    #[openrpc(deprecated_replaced_by = "getwalletinfo")]
+    #[method(name = "getinfo")]
+    fn get_info(&self) -> RpcResult<u64>;
+
+    /// Returns information about the wallet.
+    #[doc(hidden)]
+    #[method(name = "getwalletinfo")]
+    fn get_wallet_info(&self) -> RpcResult<u64>;
+}
+"#;
+    let error = crate::generate_rpc_openrpc_from_str(source, &["Rpc"]).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "<source>: method `getinfo` is replaced by `getwalletinfo`, which is not a documented \
+         method"
+    );
+
+    let generated = GenerateOptions::new("methods.rs", &["Rpc"], &std::env::temp_dir())
+        .include_doc_hidden(true)
+        .generate_from_str(source)
+        .unwrap();
+    assert!(generated.contains("    replaced_by: Some(\"getwalletinfo\"),\n"));
+    assert!(generate(&source.replace("    #[doc(hidden)]\n", "")).contains("\"getwalletinfo\" =>"));
+}
+
+#[test]
+fn result_aliases_are_unwrapped() {
+    let generated = generate(
+        r#"
+type MethodResult = RpcResult<Balance>;
+
+pub trait Rpc {
+    /// Returns the balance.
+    #[method(name = "getbalance")]
+    fn get_balance(&self) -> MethodResult;
+}
+"#,
+    );
+    assert!(generated.contains("    result: |g| g.result_of::<Balance>(\"getbalance_result\"),\n"));
+
+    // An alias defined in another file is registered with its target.
+    let source = r#"
+pub trait Rpc {
+    /// Returns the balance.
+    #[method(name = "getbalance")]
+    fn get_balance(&self) -> crate::rpc::MethodResult;
+}
+"#;
+    let generated = GenerateOptions::new("methods.rs", &["Rpc"], &std::env::temp_dir())
+        .result_alias("crate::rpc::MethodResult", "RpcResult<Balance>")
+        .generate_from_str(source)
+        .unwrap();
+    assert!(generated.contains("    result: |g| g.result_of::<Balance>(\"getbalance_result\"),\n"));
+    assert!(!generate(source).contains("g.result_of::<Balance>"));
+}
+
+#[test]
+fn malformed_overlays_are_rejected() {
+    let dir = test_dir("overlay-malformed");
+    let source = dir.join("methods.rs");
+    fs::write(&source, BLOCK_COUNT_RS).unwrap();
+    let overlay = dir.join("methods.openrpc.json");
+    let options = GenerateOptions::new(&source, &["Rpc"], &dir).rerun_if_changed(false);
+    let error = |contents: &str| {
+        fs::write(&overlay, contents).unwrap();
+        options.generate().unwrap_err().to_string()
+    };
+    let file = overlay.display();
+
+    assert!(error("{ \"x-api-id\": ").starts_with(&format!("{file}: invalid JSON: ")));
+    assert_eq!(error("[]"), format!("{file}: expected a JSON object"));
+    assert_eq!(
+        error(r#"{ "methods": [] }"#),
+        format!("{file}: `methods` must be a JSON object")
+    );
+    assert_eq!(
+        error(r#"{ "methods": { "getblockcount": true } }"#),
+        format!("{file}: overlay of method `getblockcount` must be a JSON object")
+    );
+}
+
+#[test]
+fn overlay_keys_are_passed_through() {
+    let dir = test_dir("overlay-keys");
+    let source = dir.join("methods.rs");
+    fs::write(&source, BLOCK_COUNT_RS).unwrap();
+    let overlay = dir.join("methods.openrpc.json");
+    let options = GenerateOptions::new(&source, &["Rpc"], &dir).rerun_if_changed(false);
+
+    // Keys the generator doesn't know are kept for the document to merge.
+    fs::write(
+        &overlay,
+        r#"{ "x-api-id": "wallet", "methods": { "getblockcount": { "x-internal": true } } }"#,
+    )
+    .unwrap();
+    options.generate().unwrap();
+    let generated = fs::read_to_string(dir.join("rpc_openrpc.rs")).unwrap();
+    assert!(generated.contains(r#"overlay: Some("{\"x-internal\":true}"),"#));
+    assert!(
+        generated.contains(r#"pub static DOCUMENT_OVERLAY: &str = "{\"x-api-id\":\"wallet\"}";"#)
+    );
+
+    // Methods, however, must exist.
+    fs::write(&overlay, r#"{ "methods": { "getblock": {} } }"#).unwrap();
+    assert_eq!(
+        options.generate().unwrap_err().to_string(),
+        format!(
+            "{}: `getblock` is not a documented method",
+            overlay.display()
+        )
+    );
+}
+
+#[test]
+fn method_prefixes_are_stripped() {
+    let generated = GenerateOptions::new("methods.rs", &["Rpc"], &std::env::temp_dir())
+        .strip_method_prefix("internal_")
+        .generate_from_str(
+            r#"
+pub trait Rpc {
+    /// Returns the node info.
+    #[method(name = "internal_getinfo")]
+    fn get_info(&self) -> RpcResult<u64>;
+
+    /// Returns the block count.
+    #[method(name = "getblockcount")]
+    fn get_block_count(&self) -> RpcResult<u64>;
+}
+"#,
+        )
+        .unwrap();
+    // The table stays keyed by the real names.
+    let (info, count) = generated.split_once("\"getblockcount\" =>").unwrap();
+    assert!(info.contains("\"internal_getinfo\" =>"));
+    assert!(info.contains("    public_name: Some(\"getinfo\"),\n"));
+    assert!(count.contains("    public_name: None,\n"));
+}
+
+#[test]
+fn response_payloads_document_their_payload() {
+    let generated = generate(
+        r#"
+pub trait Rpc {
+    /// Returns the best block.
+    #[method(name = "getbestblock")]
+    fn get_best_block(&self) -> ResponsePayload<'static, Block>;
+
+    /// Returns a block, if it is known.
+    #[method(name = "getblock")]
+    fn get_block(&self) -> jsonrpsee::types::ResponsePayload<'static, Option<Block>>;
+}
+"#,
+    );
+    assert!(generated.contains("    result: |g| g.result_of::<Block>(\"getbestblock_result\"),\n"));
+    assert!(
+        generated
+            .contains("    result: |g| g.result_of::<Block>(\"getblock_result\").nullable(),\n")
+    );
+}
+
+#[test]
+fn remote_types_are_documented_with_their_shadow() {
+    let generated = GenerateOptions::new("methods.rs", &["Rpc"], &std::env::temp_dir())
+        .remote_type("std::time::Duration", "DurationDef")
+        .generate_from_str(
+            r#"
+pub trait Rpc {
+    /// Sets the timeouts, returning the previous one.
+    #[method(name = "settimeouts")]
+    #[openrpc(param(name = "retries", required = true))]
+    fn set_timeouts(
+        &self,
+        timeout: std::time::Duration,
+        retries: Vec<std::time::Duration>,
+    ) -> RpcResult<Option<std::time::Duration>>;
+}
+"#,
+        )
+        .unwrap();
+    assert!(generated.contains(
+        "        _g.param::<DurationDef>(\"timeout\", crate::methods::PARAM_TIMEOUT_DESC, true),\n"
+    ));
+    assert!(generated.contains(
+        "        _g.param::<Vec < DurationDef >>(\"retries\", crate::methods::PARAM_RETRIES_DESC, true),\n"
+    ));
+    assert!(generated.contains(
+        "    result: |g| g.result_of::<DurationDef>(\"settimeouts_result\").nullable(),\n"
+    ));
+    assert!(!generated.contains("std::time::Duration"));
+}
+
+#[test]
+fn only_result_wrappers_are_unwrapped() {
+    let generated = generate(
+        r#"
+pub trait Rpc {
+    /// Returns the balance.
+    #[method(name = "getbalance")]
+    fn get_balance(&self) -> Result<Balance, ErrorObjectOwned>;
+
+    /// Returns the address, if there is one.
+    #[method(name = "getaddress")]
+    fn get_address(&self) -> jsonrpsee::core::RpcResult<Option<Address>>;
+
+    /// Lists the transactions.
+    #[method(name = "listtransactions")]
+    fn list_transactions(&self) -> RpcResult<ListResult<Transaction>>;
+
+    /// Searches the transactions.
+    #[method(name = "searchtransactions")]
+    fn search_transactions(&self) -> SearchResult<Transaction>;
+}
+"#,
+    );
+    assert!(generated.contains("    result: |g| g.result_of::<Balance>(\"getbalance_result\"),\n"));
+    assert!(
+        generated.contains(
+            "    result: |g| g.result_of::<Address>(\"getaddress_result\").nullable(),\n"
+        )
+    );
+    // Other generic types are payloads of their own.
+    assert!(generated.contains(
+        "    result: |g| g.result_of::<ListResult < Transaction >>(\"listtransactions_result\"),\n"
+    ));
+    assert!(!generated.contains("g.result_of::<Transaction>"));
+}
+
+#[test]
+fn skipped_methods_are_left_out() {
+    let generated = generate(
+        r#"
+pub trait Rpc {
+    /// Returns the block count.
+    #[method(name = "getblockcount")]
+    fn get_block_count(&self) -> RpcResult<u64>;
+
+    /// Stops the node.
+    #[method(name = "stop")]
+    #[openrpc(skip)]
+    fn stop(&self) -> RpcResult<()>;
+}
+"#,
+    );
+    assert!(generated.contains("\"getblockcount\" =>"));
+    assert!(!generated.contains("stop"));
+}
+
+#[test]
+fn extensions_are_not_params() {
+    let generated = generate(
+        r#"
+pub trait Rpc {
+    /// Returns the block count.
+    #[method(name = "getblockcount")]
+    fn get_block_count(&self) -> RpcResult<u64>;
+
+    /// Returns the caller's address.
+    #[method(name = "getpeeraddress", with_extensions)]
+    fn get_peer_address(&self, ext: &Extensions) -> RpcResult<String>;
+
+    /// Returns a block.
+    #[method(name = "getblock", with_extensions)]
+    fn get_block(&self, height: u32, ext: &jsonrpsee::Extensions) -> RpcResult<Block>;
+}
+"#,
+    );
+    let (count, rest) = generated.split_once("\"getpeeraddress\" =>").unwrap();
+    let (peer_address, block) = rest.split_once("\"getblock\" =>").unwrap();
+    assert!(count.contains("    params: |_g| vec![],\n"));
+    assert!(peer_address.contains("    params: |_g| vec![],\n"));
+    assert!(block.contains(
+        "    params: |_g| vec![
+        _g.param::<u32>(\"height\", crate::methods::PARAM_HEIGHT_DESC, true),
+    ],
+"
+    ));
+    assert!(!generated.contains("Extensions"));
+}
+
+#[test]
+fn multi_line_docs_are_kept_line_by_line() {
+    let generated = generate(
+        r#"
+pub trait Rpc {
+    /// Returns a block.
+    ///
+    /// ```json
+    /// {
+    ///   "hash": "00ab"
+    /// }
+    /// ```
+    #[method(name = "getblock")]
+    fn get_block(&self) -> RpcResult<Block>;
+}
+"#,
+    );
+    assert!(generated.contains(
+        r#"    description: concat!(
+        "Returns a block.\n",
+        "\n",
+        "```json\n",
+        "{\n",
+        "  \"hash\": \"00ab\"\n",
+        "}\n",
+        "```\n",
+    ),
+"#
+    ));
+}
+
+#[test]
+fn duplicate_method_names_are_rejected() {
+    let error = crate::generate_rpc_openrpc_from_str(
+        &BLOCK_COUNT_RS.replace(
+            "}\n",
+            "
+    /// Returns the block count, too.
+    #[method(name = \"getblockcount\")]
+    fn block_count(&self) -> RpcResult<u64>;
+}
+",
+        ),
+        &["Rpc"],
+    )
+    .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "<source>: methods `Rpc::get_block_count` and `Rpc::block_count` are both named `getblockcount`"
+    );
+}
+
+/// A method taking a `Vec<String>`, whose requiredness can't be told from its type.
+const SEND_RS: &str = r#"
+pub trait Rpc {
+    /// Sends coins to each address.
+    #[method(name = "send")]
+    fn send(&self, addresses: Vec<String>) -> RpcResult<send::Response>;
+}
+"#;
+
+#[test]
+fn vec_requiredness_is_stated_by_attribute_or_const() {
+    let generated = generate(&SEND_RS.replace(
+        "#[method",
+        "#[openrpc(param(name = \"addresses\", required = false))]\n    #[method",
+    ));
+    assert!(generated.contains(
+        "        _g.param::<Vec < String >>(\"addresses\", crate::methods::PARAM_ADDRESSES_DESC, false),\n"
+    ));
+
+    let generated = generate(&format!(
+        "pub mod send {{\n    pub const PARAM_ADDRESSES_REQUIRED: bool = false;\n}}\n{SEND_RS}"
+    ));
+    assert!(generated.contains(
+        "        _g.param::<Vec < String >>(\"addresses\", crate::methods::PARAM_ADDRESSES_DESC, crate::methods::send::PARAM_ADDRESSES_REQUIRED),\n"
+    ));
+}
+
+#[test]
+fn unknown_requiredness_is_reported() {
+    let required = "        _g.param::<Vec < String >>(\"addresses\", crate::methods::PARAM_ADDRESSES_DESC, true),\n";
+    for (check, expected_warnings) in [
+        (
+            None,
+            vec![
+                "method 'send': parameter 'addresses' is documented as required, as neither \
+                 `#[openrpc(param(name = \"addresses\", required = ...))]` nor \
+                 `send::PARAM_ADDRESSES_REQUIRED` is defined",
+            ],
+        ),
+        (Some(DocCheck::Allow), vec![]),
+    ] {
+        let mut options = GenerateOptions::new("methods.rs", &["Rpc"], &std::env::temp_dir());
+        if let Some(check) = check {
+            options = options.missing_required(check);
+        }
+        let mut warnings = vec![];
+        let (_, generated) = options
+            .generate_from_sources(&options.sources(SEND_RS).unwrap(), &mut warnings)
+            .unwrap();
+        assert!(generated.contains(required));
+        assert_eq!(warnings, expected_warnings);
+    }
+
+    let error = GenerateOptions::new("methods.rs", &["Rpc"], &std::env::temp_dir())
+        .missing_required(DocCheck::Deny)
+        .generate_from_str(SEND_RS)
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "methods.rs: method 'send': whether parameter 'addresses' is required isn't known, as \
+         neither `#[openrpc(param(name = \"addresses\", required = ...))]` nor \
+         `send::PARAM_ADDRESSES_REQUIRED` is defined"
+    );
+}
+
+#[test]
+fn doc_hidden_methods_are_left_out_by_the_attribute_unless_included() {
+    // The trait is kept as it is, so only the table following it is looked at.
+    let table = |attr: &str| {
+        let expanded =
+            crate::openrpc_methods(attr.parse().unwrap(), DOC_HIDDEN_RS.parse().unwrap());
+        let expanded = expanded.to_string();
+        expanded
+            .split_once("pub static METHODS")
+            .unwrap()
+            .1
+            .to_string()
+    };
+    let generated = table("");
+    assert!(generated.contains("\"getblockcount\" =>"));
+    assert!(!generated.contains("setmocktime"));
+
+    let generated = table("include_doc_hidden");
+    assert!(generated.contains("\"getblockcount\" =>"));
+    assert!(generated.contains("\"setmocktime\" =>"));
+}
+
+#[test]
+fn doc_lines_lose_one_leading_space() {
+    let generated = generate(
+        "
+pub trait Rpc {
+    /// Returns the block count.
+    ///
+    ///   Indented further.
+    ///Without a space.
+    /// ß follows the space.
+    ///€ follows the slashes.
+    #[method(name = \"getblockcount\")]
+    fn get_block_count(&self) -> RpcResult<u64>;
+}
+",
+    );
+    assert!(generated.contains(
+        r#"    description: concat!(
+        "Returns the block count.\n",
+        "\n",
+        "  Indented further.\n",
+        "Without a space.\n",
+        "ß follows the space.\n",
+        "€ follows the slashes.\n",
+    ),
+"#
+    ));
+}
+
+#[test]
+fn renamed_args_are_documented_under_their_new_name() {
+    let generated = generate(
+        r#"
+pub trait Rpc {
+    /// Returns a block.
+    #[method(name = "getblock")]
+    fn get_block(
+        &self,
+        #[argument(rename = "blockHash")] block_hash: String,
+        verbosity: u8,
+    ) -> RpcResult<Block>;
+}
+"#,
+    );
+    assert!(generated.contains(
+        "    params: |_g| vec![
+        _g.param::<String>(\"blockHash\", crate::methods::PARAM_BLOCK_HASH_DESC, true),
+        _g.param::<u8>(\"verbosity\", crate::methods::PARAM_VERBOSITY_DESC, true),
+    ],
+"
+    ));
+}
+
+#[test]
+fn options_within_transparent_wrappers_are_optional() {
+    let generated = generate(
+        r#"
+pub trait Rpc {
+    /// Lists the blocks in a range.
+    #[method(name = "listblocks")]
+    fn list_blocks(
+        &self,
+        count: Box<Option<u64>>,
+        heights: Option<Vec<u64>>,
+        amounts: HashMap<String, Option<u64>>,
+    ) -> RpcResult<Vec<Block>>;
+}
+"#,
+    );
+    assert!(generated.contains(
+        "    params: |_g| vec![
+        _g.param::<u64>(\"count\", crate::methods::PARAM_COUNT_DESC, false).nullable(),
+        _g.param::<Vec < u64 >>(\"heights\", crate::methods::PARAM_HEIGHTS_DESC, false).nullable(),
+        _g.param::<HashMap < String , Option < u64 > >>(\"amounts\", crate::methods::PARAM_AMOUNTS_DESC, true),
+    ],
+"
+    ));
+}
+
+#[test]
+fn supertrait_methods_are_documented() {
+    let options = GenerateOptions::new("methods.rs", &["Wallet"], &std::env::temp_dir());
+    let source = r#"
+pub trait Core: Send + Sync {
+    /// Returns the block count.
+    #[method(name = "getblockcount")]
+    fn get_block_count(&self) -> RpcResult<u64>;
+}
+
+pub trait Node: Core + Plugin {
+    /// Returns the peer count.
+    #[method(name = "getpeercount")]
+    fn get_peer_count(&self) -> RpcResult<u64>;
+}
+
+pub trait Wallet: Node {
+    /// Returns the balance.
+    #[method(name = "getbalance")]
+    fn get_balance(&self) -> RpcResult<u64>;
+}
+"#;
+    let mut warnings = vec![];
+    let (methods, _) = options
+        .generate_from_sources(&options.sources(source).unwrap(), &mut warnings)
+        .unwrap();
+
+    let mut commands: Vec<_> = methods
+        .iter()
+        .map(|method| method.command.as_str())
+        .collect();
+    commands.sort();
+    assert_eq!(commands, ["getbalance", "getblockcount", "getpeercount"]);
+    assert_eq!(
+        warnings,
+        [
+            "supertrait `Plugin` of trait `Node` isn't defined in the source files, so its \
+          methods aren't documented"
+        ]
+    );
+}
+
+#[test]
+fn cfg_gated_methods_fall_back_when_disabled() {
+    let generated = generate(
+        r#"
+pub trait Rpc {
+    /// Stakes the given amount.
+    #[cfg(feature = "staking")]
+    #[method(name = "stake")]
+    fn stake(&self, amount: u64) -> RpcResult<StakeInfo>;
+}
+"#,
+    );
+    assert!(generated.contains("\"stake\" =>"));
+    assert!(generated.contains("    available: cfg!(feature = \"staking\"),\n"));
+    assert!(generated.contains(
+        "    params: {
+        #[cfg(feature = \"staking\")]
+        let params: fn(&mut openrpsee::openrpc::Generator) -> Vec<openrpsee::openrpc::ContentDescriptor> = |_g| vec![
+        _g.param::<u64>(\"amount\", crate::methods::PARAM_AMOUNT_DESC, true),
+    ];
+        #[cfg(not(feature = \"staking\"))]
+        let params: fn(&mut openrpsee::openrpc::Generator) -> Vec<openrpsee::openrpc::ContentDescriptor> = |_g| vec![];
+        params
+    },
+"
+    ));
+    assert!(generated.contains(
+        "        #[cfg(not(feature = \"staking\"))]
+        let result: fn(&mut openrpsee::openrpc::Generator) -> openrpsee::openrpc::ContentDescriptor = |g| g.item::<()>(\"unavailable\");
+"
+    ));
+}
+
+#[test]
+fn maps_are_documented_as_params_and_results() {
+    let generated = generate(
+        r#"
+pub const PARAM_ADDRESSES_REQUIRED: bool = false;
+
+pub trait Rpc {
+    /// Returns the balance of each account.
+    #[method(name = "getbalances")]
+    fn get_balances(
+        &self,
+        minimums: HashMap<String, u64>,
+        addresses: Vec<String>,
+    ) -> RpcResult<HashMap<String, u64>>;
+}
+"#,
+    );
+    assert!(generated.contains(
+        "    params: |_g| vec![
+        _g.param::<HashMap < String , u64 >>(\"minimums\", crate::methods::PARAM_MINIMUMS_DESC, true),
+        _g.param::<Vec < String >>(\"addresses\", crate::methods::PARAM_ADDRESSES_DESC, crate::methods::PARAM_ADDRESSES_REQUIRED),
+    ],
+"
+    ));
+    assert!(generated.contains(
+        "    result: |g| g.result_of::<HashMap < String , u64 >>(\"getbalances_result\"),\n"
+    ));
+}
+
+#[test]
+fn param_schemas_can_be_overridden_by_attribute() {
+    let generated = GenerateOptions::new("methods.rs", &["Rpc"], &std::env::temp_dir())
+        .type_override("BlockHash", SchemaOverride::base64_bytes())
+        .generate_from_str(
+            r#"
+pub struct BlockHash([u8; 32]);
+
+pub trait Rpc {
+    /// Returns the hashes of a block and its parent.
+    #[method(name = "getblockhashes")]
+    #[openrpc(param(name = "hash", schema = "String", format = "hex"))]
+    fn get_block_hashes(&self, hash: BlockHash, parent: BlockHash) -> RpcResult<u64>;
+}
+"#,
+        )
+        .unwrap();
+    // The attribute takes precedence over the type override.
+    assert!(generated.contains(
+        "    params: |_g| vec![
+        _g.param::<String>(\"hash\", crate::methods::PARAM_HASH_DESC, true).with_format(\"hex\"),
+        _g.param::<String>(\"parent\", crate::methods::PARAM_PARENT_DESC, true).with_format(\"base64\"),
+    ],
+"
+    ));
+}
+
+#[test]
+fn referenced_types_are_overridden() {
+    let dir = test_dir("referenced-types");
+    let methods_rs = dir.join("methods.rs");
+    fs::write(
+        &methods_rs,
+        r#"
+pub trait Rpc {
+    /// Unlocks the wallet.
+    #[method(name = "walletpassphrase")]
+    fn wallet_passphrase(
+        &self,
+        passphrase: age::secrecy::SecretString,
+        timeout: u64,
+    ) -> RpcResult<Balance>;
+
+    /// Returns the balance.
+    #[method(name = "getbalance")]
+    fn get_balance(&self, minconf: u32) -> RpcResult<Balance>;
+}
+"#,
+    )
+    .unwrap();
+
+    let types = crate::referenced_types(methods_rs.to_str().unwrap(), &["Rpc"]).unwrap();
+    assert_eq!(types, ["Balance", "String", "u32", "u64"]);
+}
+
+#[test]
+fn optional_params_accept_null() {
+    let generated = generate(
+        r#"
+pub trait Rpc {
+    /// Returns a block.
+    #[method(name = "getblock")]
+    fn get_block(&self, height: u32, verbosity: Option<u8>) -> RpcResult<Block>;
+}
+"#,
+    );
+    assert!(generated.contains(
+        "    params: |_g| vec![
+        _g.param::<u32>(\"height\", crate::methods::PARAM_HEIGHT_DESC, true),
+        _g.param::<u8>(\"verbosity\", crate::methods::PARAM_VERBOSITY_DESC, false).nullable(),
+    ],
+"
+    ));
+}
+
+#[test]
+fn tuples_are_documented_as_params_and_results() {
+    let generated = generate(
+        r#"
+pub trait Rpc {
+    /// Returns the hash and height of the best block in a range.
+    #[method(name = "getbestinrange")]
+    fn get_best_in_range(&self, range: (u64, u64)) -> RpcResult<(String, u64)>;
+}
+"#,
+    );
+    assert!(generated.contains(
+        "        _g.param::<(u64 , u64)>(\"range\", crate::methods::PARAM_RANGE_DESC, true),\n"
+    ));
+    assert!(
+        generated.contains(
+            "    result: |g| g.result_of::<(String , u64)>(\"getbestinrange_result\"),\n"
+        )
+    );
+}
+
+#[test]
+fn borrowed_params_are_documented_as_owned() {
+    let generated = generate(
+        r#"
+pub trait Rpc {
+    /// Sends a raw transaction.
+    #[method(name = "sendrawtransaction")]
+    #[openrpc(param(name = "raw", required = true))]
+    fn send_raw_transaction(
+        &self,
+        label: &str,
+        raw: &'a [u8],
+        comment: Option<&str>,
+    ) -> RpcResult<String>;
+}
+"#,
+    );
+    assert!(generated.contains(
+        "    params: |_g| vec![
+        _g.param::<String>(\"label\", crate::methods::PARAM_LABEL_DESC, true),
+        _g.param::<Vec < u8 >>(\"raw\", crate::methods::PARAM_RAW_DESC, true),
+        _g.param::<String>(\"comment\", crate::methods::PARAM_COMMENT_DESC, false).nullable(),
+    ],
+"
+    ));
+}
+
+#[test]
+fn params_can_be_deprecated() {
+    let generated = generate(
+        r#"
+pub trait Rpc {
+    /// Returns the balance.
+    #[openrpc(param(name = "minconf", deprecated = "Use `minheight`."))]
+    #[method(name = "getbalance")]
+    fn get_balance(
+        &self,
+        account: String,
+        minconf: u32,
+        #[openrpc(deprecated)] verbose: bool,
+    ) -> RpcResult<u64>;
+}
+"#,
+    );
+    assert!(generated.contains(
+        "        _g.param::<String>(\"account\", crate::methods::PARAM_ACCOUNT_DESC, true),\n"
+    ));
+    assert!(generated.contains(
+        "        _g.param::<u32>(\"minconf\", crate::methods::PARAM_MINCONF_DESC, true).deprecated(Some(\"Use `minheight`.\")),\n"
+    ));
+    assert!(generated.contains(
+        "        _g.param::<bool>(\"verbose\", crate::methods::PARAM_VERBOSE_DESC, true).deprecated(None),\n"
+    ));
+}
+
+#[test]
+fn async_methods_are_documented_like_sync_ones() {
+    let sync_rs = r#"
+pub trait Rpc {
+    /// Returns a block.
+    #[method(name = "getblock")]
+    fn get_block(&self, hash: String, verbosity: Option<u8>) -> RpcResult<Option<Block>>;
+
+    /// Subscribes to new blocks.
+    #[subscription(name = "subscribeBlocks" => "block", unsubscribe = "unsubscribeBlocks", item = Block)]
+    fn blocks(&self) -> SubscriptionResult;
+}
+"#;
+    let async_rs = sync_rs.replace("    fn ", "    async fn ");
+    assert_eq!(async_rs.matches("async fn").count(), 2);
+    assert_eq!(generate(&async_rs), generate(sync_rs));
+}
+
+#[test]
+fn summaries_can_be_set_by_attribute() {
+    let generated = generate(
+        r#"
+pub trait Rpc {
+    /// Returns general information about the state of the wallet, including its balances and the number of keys in its keypool.
+    ///
+    /// Balances are in zatoshis.
+    #[method(name = "getwalletinfo")]
+    #[openrpc(summary = "Returns the wallet state.")]
+    fn get_wallet_info(&self) -> RpcResult<WalletInfo>;
+}
+"#,
+    );
+    assert!(generated.contains("    summary: Some(\"Returns the wallet state.\"),\n"));
+    assert!(generated.contains(
+        "        \"Returns general information about the state of the wallet, including its balances and the number of keys in its keypool.\\n\",\n"
+    ));
+}
+
+#[test]
+fn method_attributes_without_a_name_are_rejected() {
+    let error = crate::generate_rpc_openrpc_from_str(
+        &BLOCK_COUNT_RS.replace("name = \"getblockcount\"", "aliases = [\"blockcount\"]"),
+        &["Rpc"],
+    )
+    .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "<source>: invalid method `get_block_count`: `method` attribute without a `name`"
+    );
+
+    let error = crate::generate_rpc_openrpc_from_str(
+        &BLOCK_COUNT_RS.replace("name = \"getblockcount\"", "name = getblockcount"),
+        &["Rpc"],
+    )
+    .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "<source>: invalid method `get_block_count`: `method` attribute without a readable \
+         `name`: expected string literal"
+    );
+}
@@ -0,0 +1,71 @@
+//! A representative wallet RPC, documented by `tests/valid_document.rs`.
+
+#![allow(dead_code)]
+
+use std::collections::BTreeMap;
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A transaction in the wallet.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Transaction {
+    /// The transaction ID, as hex.
+    pub txid: String,
+    /// The amount, negative for spends.
+    pub amount: i64,
+    /// The number of confirmations, if the transaction is mined.
+    pub confirmations: Option<u32>,
+}
+
+/// The state of the wallet.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WalletInfo {
+    /// The confirmed balance.
+    pub balance: u64,
+    /// The most recent transactions.
+    pub recent: Vec<Transaction>,
+    /// The number of addresses under each label.
+    pub labels: BTreeMap<String, u32>,
+}
+
+pub const PARAM_ADDRESSES_DESC: &str = "The addresses to list transactions for, or all.";
+pub const PARAM_MINCONF_DESC: &str = "The minimum number of confirmations.";
+pub const PARAM_TXID_DESC: &str = "The transaction ID, as hex.";
+pub const PARAM_ADDRESS_DESC: &str = "The address to label.";
+pub const PARAM_LABEL_DESC: &str = "The new label.";
+
+#[rpc(server)]
+pub trait Wallet {
+    /// Returns the state of the wallet.
+    #[method(name = "getwalletinfo")]
+    async fn get_wallet_info(&self) -> RpcResult<WalletInfo>;
+
+    /// Lists the wallet's transactions.
+    ///
+    /// Transactions with fewer than `minconf` confirmations are left out.
+    #[method(name = "listtransactions")]
+    #[openrpc(param(name = "addresses", required = false))]
+    async fn list_transactions(
+        &self,
+        addresses: Vec<String>,
+        minconf: Option<u32>,
+    ) -> RpcResult<Vec<Transaction>>;
+
+    /// Returns a transaction, if the wallet has it.
+    #[method(name = "gettransaction")]
+    async fn get_transaction(
+        &self,
+        #[argument(rename = "txId")] txid: String,
+    ) -> RpcResult<Option<Transaction>>;
+
+    /// Returns the confirmed balance.
+    #[deprecated(note = "Use getwalletinfo.")]
+    #[method(name = "getbalance")]
+    async fn get_balance(&self, minconf: Option<u32>) -> RpcResult<u64>;
+
+    /// Labels an address.
+    #[method(name = "setlabel")]
+    async fn set_label(&self, address: &str, label: Option<&str>) -> RpcResult<()>;
+}
@@ -0,0 +1,134 @@
+/// Lookup table for JSON-RPC methods.
+#[allow(unused_qualifications)]
+pub static METHODS: ::phf::Map<&str, openrpsee::openrpc::RpcMethod> = ::phf::phf_map! {
+"getwalletinfo" => openrpsee::openrpc::RpcMethod {
+    aliases: &[],
+    tags: &[],
+    description: concat!(
+        "Returns the state of the wallet.\n",
+    ),
+    summary: None,
+    available: true,
+    params: |_g| vec![],
+    result: |g| g.result_of::<WalletInfo>("getwalletinfo_result"),
+    deprecated: false,
+    replaced_by: None,
+    deprecation_note: None,
+    subscription: false,
+    unsubscribe: None,
+    cost: None,
+    result_examples: &[],
+    examples: &[],
+    errors: &[],
+    param_structure: openrpsee::openrpc::ParamStructure::Either,
+    public_name: None,
+    overlay: None,
+},
+"listtransactions" => openrpsee::openrpc::RpcMethod {
+    aliases: &[],
+    tags: &[],
+    description: concat!(
+        "Lists the wallet's transactions.\n",
+        "\n",
+        "Transactions with fewer than `minconf` confirmations are left out.\n",
+    ),
+    summary: None,
+    available: true,
+    params: |_g| vec![
+        _g.param::<Vec < String >>("addresses", crate::methods::PARAM_ADDRESSES_DESC, false),
+        _g.param::<u32>("minconf", crate::methods::PARAM_MINCONF_DESC, false).nullable(),
+    ],
+    result: |g| g.result_of::<Vec < Transaction >>("listtransactions_result"),
+    deprecated: false,
+    replaced_by: None,
+    deprecation_note: None,
+    subscription: false,
+    unsubscribe: None,
+    cost: None,
+    result_examples: &[],
+    examples: &[],
+    errors: &[],
+    param_structure: openrpsee::openrpc::ParamStructure::Either,
+    public_name: None,
+    overlay: None,
+},
+"gettransaction" => openrpsee::openrpc::RpcMethod {
+    aliases: &[],
+    tags: &[],
+    description: concat!(
+        "Returns a transaction, if the wallet has it.\n",
+    ),
+    summary: None,
+    available: true,
+    params: |_g| vec![
+        _g.param::<String>("txId", crate::methods::PARAM_TXID_DESC, true),
+    ],
+    result: |g| g.result_of::<Transaction>("gettransaction_result").nullable(),
+    deprecated: false,
+    replaced_by: None,
+    deprecation_note: None,
+    subscription: false,
+    unsubscribe: None,
+    cost: None,
+    result_examples: &[],
+    examples: &[],
+    errors: &[],
+    param_structure: openrpsee::openrpc::ParamStructure::Either,
+    public_name: None,
+    overlay: None,
+},
+"getbalance" => openrpsee::openrpc::RpcMethod {
+    aliases: &[],
+    tags: &[],
+    description: concat!(
+        "Returns the confirmed balance.\n",
+    ),
+    summary: None,
+    available: true,
+    params: |_g| vec![
+        _g.param::<u32>("minconf", crate::methods::PARAM_MINCONF_DESC, false).nullable(),
+    ],
+    result: |g| g.result_of::<u64>("getbalance_result"),
+    deprecated: true,
+    replaced_by: None,
+    deprecation_note: Some("Use getwalletinfo."),
+    subscription: false,
+    unsubscribe: None,
+    cost: None,
+    result_examples: &[],
+    examples: &[],
+    errors: &[],
+    param_structure: openrpsee::openrpc::ParamStructure::Either,
+    public_name: None,
+    overlay: None,
+},
+"setlabel" => openrpsee::openrpc::RpcMethod {
+    aliases: &[],
+    tags: &[],
+    description: concat!(
+        "Labels an address.\n",
+    ),
+    summary: None,
+    available: true,
+    params: |_g| vec![
+        _g.param::<String>("address", crate::methods::PARAM_ADDRESS_DESC, true),
+        _g.param::<String>("label", crate::methods::PARAM_LABEL_DESC, false).nullable(),
+    ],
+    result: |g| g.result_of::<()>("setlabel_result"),
+    deprecated: false,
+    replaced_by: None,
+    deprecation_note: None,
+    subscription: false,
+    unsubscribe: None,
+    cost: None,
+    result_examples: &[],
+    examples: &[],
+    errors: &[],
+    param_structure: openrpsee::openrpc::ParamStructure::Either,
+    public_name: None,
+    overlay: None,
+},
+};
+
+/// Fields merged over the top level of the OpenRPC document, as a JSON object.
+pub static DOCUMENT_OVERLAY: &str = "{}";
\ No newline at end of file
@@ -0,0 +1,93 @@
+//! Checks the document generated for a representative trait end to end.
+
+#![cfg(feature = "validate")]
+
+use openrpsee::openrpc::{Info, OpenRpc, validate::assert_valid_document};
+
+#[path = "fixtures/wallet.rs"]
+mod methods;
+
+// Single-line descriptions are still emitted with `concat!`.
+#[allow(clippy::useless_concat)]
+mod generated {
+    use crate::methods::*;
+
+    include!("fixtures/wallet_openrpc.rs");
+}
+
+#[test]
+fn generated_table_is_current() {
+    let generated =
+        openrpsee::generate_rpc_openrpc_from_str(include_str!("fixtures/wallet.rs"), &["Wallet"])
+            .unwrap();
+    assert_eq!(generated, include_str!("fixtures/wallet_openrpc.rs"));
+}
+
+#[test]
+fn document_is_valid() {
+    let info = Info::builder().title("Wallet").version("1.0.0").build();
+    let doc = assert_valid_document(generated::METHODS.entries(), info).unwrap();
+
+    let doc = doc
+        .to_value_with_overlay(generated::DOCUMENT_OVERLAY)
+        .unwrap();
+    let methods = doc["methods"].as_array().unwrap();
+    let names: Vec<_> = methods.iter().map(|method| &method["name"]).collect();
+    assert_eq!(
+        names,
+        [
+            "getbalance",
+            "gettransaction",
+            "getwalletinfo",
+            "listtransactions",
+            "setlabel"
+        ]
+    );
+
+    let get_balance = &methods[0];
+    assert_eq!(get_balance["deprecated"], true);
+    assert_eq!(get_balance["x-deprecation-note"], "Use getwalletinfo.");
+    assert_eq!(
+        get_balance["params"][0]["required"],
+        serde_json::Value::Null
+    );
+
+    // Renamed with `#[argument(rename = "txId")]`, which `#[rpc]` compiles.
+    let get_transaction = &methods[1];
+    assert_eq!(get_transaction["params"][0]["name"], "txId");
+
+    let list_transactions = &methods[3];
+    assert_eq!(list_transactions["params"][0]["schema"]["type"], "array");
+    assert_eq!(
+        list_transactions["params"][1]["schema"]["type"],
+        serde_json::json!(["integer", "null"])
+    );
+
+    // Borrowed arguments are documented as the owned types they deserialize into.
+    let set_label = &methods[4];
+    assert_eq!(set_label["params"][0]["schema"]["type"], "string");
+    assert_eq!(
+        set_label["params"][1]["schema"]["type"],
+        serde_json::json!(["string", "null"])
+    );
+
+    let schemas = doc["components"]["schemas"].as_object().unwrap();
+    assert_eq!(
+        schemas.keys().collect::<Vec<_>>(),
+        ["Transaction", "WalletInfo"]
+    );
+    assert_eq!(
+        schemas["WalletInfo"]["properties"]["recent"]["items"],
+        serde_json::json!({ "$ref": "#/components/schemas/Transaction" })
+    );
+}
+
+#[test]
+fn document_round_trips() {
+    let info = Info::builder().title("Wallet").version("1.0.0").build();
+    let doc = OpenRpc::build(info, generated::METHODS.entries());
+
+    let json = serde_json::to_string(&doc).unwrap();
+    let read_back = serde_json::from_str::<OpenRpc>(&json).unwrap();
+    assert_eq!(serde_json::to_string(&read_back).unwrap(), json);
+}